@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{data_dir, AttError, AttackData, Index};
+
+/// Bump whenever `AttackObject` gains, loses or renames a field, so older caches are ignored
+const CACHE_FORMAT: u32 = 7;
+
+#[derive(Serialize)]
+struct CacheRef<'a> {
+    format: u32,
+    data: &'a AttackData,
+}
+
+#[derive(Deserialize)]
+struct Cache {
+    format: u32,
+    data: AttackData,
+}
+
+/// Where the parsed copy of a matrix file is cached: under `cache/` in the data directory, so
+/// matrices passed with `--file` never get a cache file written next to them
+pub fn cache_path(matrix_path: &Path) -> Result<PathBuf, AttError> {
+    Ok(cache_path_in(&data_dir()?.join("cache"), matrix_path))
+}
+
+/// `cache_path` for the given cache directory: `matrix.json` -> `matrix-<hash of its full path>.cache.json`,
+/// so two matrices with the same file name get separate caches
+pub fn cache_path_in(cache_dir: &Path, matrix_path: &Path) -> PathBuf {
+    let full_path = fs::canonicalize(matrix_path).unwrap_or_else(|_| matrix_path.to_path_buf());
+    let stem = matrix_path.file_stem().unwrap_or_default().to_string_lossy();
+    cache_dir.join(format!("{}-{:016x}.cache.json", stem, fnv1a(full_path.as_os_str().as_encoded_bytes())))
+}
+
+// FNV-1a, which unlike std's hashers is guaranteed to give the same value across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+/// The cached copy of the matrix at `matrix_path`, if `cache` holds one at least as new as the matrix.
+/// Cache problems are never fatal: a missing, stale or unreadable cache just gives `None`.
+pub fn read_cache(cache: &Path, matrix_path: &Path) -> Option<AttackData> {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    if modified(cache)? < modified(matrix_path)? {
+        return None;
+    }
+    
+    let cache: Cache = serde_json::from_str(&fs::read_to_string(cache).ok()?).ok()?;
+    if cache.format != CACHE_FORMAT {
+        return None;
+    }
+    let mut data = cache.data;
+    data.index = Index::build(&data.objects);
+    Some(data)
+}
+
+/// Writes `data` to `cache`, creating its directory. The cache only holds the fields we model,
+/// without whitespace or null fields, so it is much smaller than the bundle and parses faster.
+pub fn write_cache(cache: &Path, data: &AttackData) -> std::io::Result<()> {
+    let mut value = serde_json::to_value(CacheRef { format: CACHE_FORMAT, data })?;
+    strip_nulls(&mut value);
+    if let Some(dir) = cache.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(cache, serde_json::to_string(&value)?)
+}
+
+// Missing fields read back as `None`, the same as nulls; most of an object's optional fields are unset
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, field| !field.is_null());
+            map.values_mut().for_each(strip_nulls);
+        },
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {},
+    }
+}
@@ -0,0 +1,249 @@
+// Shell completion scripts generated from the clap command tree, so they track
+// the Commands enum without being maintained by hand
+use clap::{Arg, Command, ValueEnum, ValueHint};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+// The parts of a (sub)command the scripts need: its flags and their fixed values
+struct CommandSpec {
+    name: String,
+    about: String,
+    flags: Vec<FlagSpec>,
+}
+
+struct FlagSpec {
+    names: Vec<String>,
+    help: String,
+    takes_value: bool,
+    takes_path: bool,
+    values: Vec<String>,
+}
+
+fn first_line(text: Option<String>) -> String {
+    text.and_then(|text| text.lines().next().map(str::to_string)).unwrap_or_default()
+}
+
+fn flag_spec(arg: &Arg) -> Option<FlagSpec> {
+    if arg.is_hide_set() || arg.is_positional() {
+        return None;
+    }
+    let mut names: Vec<String> = arg.get_long().map(|long| format!("--{}", long)).into_iter().collect();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("-{}", short));
+    }
+    Some(FlagSpec {
+        names,
+        help: first_line(arg.get_help().map(ToString::to_string)),
+        takes_value: arg.get_action().takes_values(),
+        takes_path: matches!(arg.get_value_hint(), ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath),
+        values: arg.get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+    })
+}
+
+fn command_spec(command: &Command) -> CommandSpec {
+    CommandSpec {
+        name: command.get_name().to_string(),
+        about: first_line(command.get_about().map(ToString::to_string)),
+        flags: command.get_arguments().filter_map(flag_spec).collect(),
+    }
+}
+
+pub fn generate(shell: Shell, mut command: Command) -> String {
+    // Building propagates the global flags into every subcommand
+    command.build();
+    let bin = command.get_name().to_string();
+    let root = command_spec(&command);
+    let subcommands: Vec<CommandSpec> = command.get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name() != "help")
+        .map(command_spec)
+        .collect();
+    
+    match shell {
+        Shell::Bash => bash(&bin, &root, &subcommands),
+        Shell::Zsh => zsh(&bin, &root, &subcommands),
+        Shell::Fish => fish(&bin, &root, &subcommands),
+        Shell::Powershell => powershell(&bin, &root, &subcommands),
+    }
+}
+
+fn single_quoted(text: &str) -> String {
+    text.replace('\'', "'\\''")
+}
+
+fn bash(bin: &str, root: &CommandSpec, subcommands: &[CommandSpec]) -> String {
+    let function = format!("_{}", bin.replace('-', "_"));
+    let names: Vec<&str> = subcommands.iter().map(|sub| sub.name.as_str()).collect();
+    let mut script = String::new();
+    
+    script.push_str(&format!("{}() {{\n", function));
+    script.push_str("    local cur prev cmd word\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    script.push_str("    cmd=\"\"\n");
+    script.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+    script.push_str(&format!("        case \"$word\" in\n            {}) cmd=\"$word\"; break ;;\n        esac\n", names.join("|")));
+    script.push_str("    done\n\n");
+    
+    script.push_str("    case \"$cmd $prev\" in\n");
+    for spec in std::iter::once(root).chain(subcommands) {
+        let prefix = if std::ptr::eq(spec, root) { "" } else { spec.name.as_str() };
+        for flag in spec.flags.iter().filter(|flag| flag.takes_value) {
+            let words = if flag.values.is_empty() { None } else { Some(flag.values.join(" ")) };
+            for name in &flag.names {
+                let reply = match &words {
+                    Some(words) => format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", words),
+                    None if flag.takes_path => "COMPREPLY=($(compgen -f -- \"$cur\"))".to_string(),
+                    None => "COMPREPLY=()".to_string(),
+                };
+                script.push_str(&format!("        \"{} {}\") {}; return ;;\n", prefix, name, reply));
+            }
+        }
+    }
+    script.push_str("    esac\n\n");
+    
+    script.push_str("    case \"$cmd\" in\n");
+    let root_words: Vec<&str> = root.flags.iter().flat_map(|flag| flag.names.iter().map(String::as_str)).chain(names.iter().copied()).collect();
+    script.push_str(&format!("        \"\") COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n", root_words.join(" ")));
+    for sub in subcommands {
+        let words: Vec<&str> = sub.flags.iter().flat_map(|flag| flag.names.iter().map(String::as_str)).collect();
+        script.push_str(&format!("        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n", sub.name, words.join(" ")));
+    }
+    script.push_str("    esac\n}\n\n");
+    script.push_str(&format!("complete -o default -F {} {}\n", function, bin));
+    script
+}
+
+fn zsh_flag(flag: &FlagSpec) -> Vec<String> {
+    let help = single_quoted(&flag.help.replace('[', "\\[").replace(']', "\\]").replace(':', "\\:"));
+    let action = if !flag.takes_value {
+        String::new()
+    } else if flag.takes_path {
+        ":path:_files".to_string()
+    } else if flag.values.is_empty() {
+        ":value: ".to_string()
+    } else {
+        format!(":value:({})", flag.values.join(" "))
+    };
+    flag.names.iter().map(|name| format!("'{}[{}]{}'", name, help, action)).collect()
+}
+
+fn zsh(bin: &str, root: &CommandSpec, subcommands: &[CommandSpec]) -> String {
+    let function = format!("_{}", bin.replace('-', "_"));
+    let mut script = format!("#compdef {}\n\n{}() {{\n", bin, function);
+    script.push_str("    local -a subcommands\n    subcommands=(\n");
+    for sub in subcommands {
+        script.push_str(&format!("        '{}:{}'\n", sub.name, single_quoted(&sub.about.replace(':', "\\:"))));
+    }
+    script.push_str("    )\n\n    _arguments -C \\\n");
+    for flag in root.flags.iter().flat_map(zsh_flag) {
+        script.push_str(&format!("        {} \\\n", flag));
+    }
+    script.push_str("        '1:command:->command' \\\n        '*::arg:->args'\n\n");
+    script.push_str("    case $state in\n        command) _describe 'command' subcommands ;;\n        args)\n            case $words[1] in\n");
+    for sub in subcommands {
+        script.push_str(&format!("                {}) _arguments", sub.name));
+        for flag in sub.flags.iter().flat_map(zsh_flag) {
+            script.push_str(&format!(" \\\n                    {}", flag));
+        }
+        script.push_str(" ;;\n");
+    }
+    script.push_str("            esac ;;\n    esac\n}\n\n");
+    script.push_str(&format!("{} \"$@\"\n", function));
+    script
+}
+
+fn fish_flag(bin: &str, condition: &str, flag: &FlagSpec) -> String {
+    let mut line = format!("complete -c {} -n '{}'", bin, condition);
+    for name in &flag.names {
+        match name.strip_prefix("--") {
+            Some(long) => line.push_str(&format!(" -l {}", long)),
+            None => line.push_str(&format!(" -s {}", name.trim_start_matches('-'))),
+        }
+    }
+    if !flag.help.is_empty() {
+        line.push_str(&format!(" -d '{}'", flag.help.replace('\'', "\\'")));
+    }
+    if flag.takes_value {
+        if flag.takes_path {
+            line.push_str(" -r -F");
+        } else if flag.values.is_empty() {
+            line.push_str(" -x");
+        } else {
+            line.push_str(&format!(" -x -a '{}'", flag.values.join(" ")));
+        }
+    }
+    line.push('\n');
+    line
+}
+
+fn fish(bin: &str, root: &CommandSpec, subcommands: &[CommandSpec]) -> String {
+    let mut script = String::new();
+    for flag in &root.flags {
+        script.push_str(&fish_flag(bin, "__fish_use_subcommand", flag));
+    }
+    for sub in subcommands {
+        script.push_str(&format!(
+            "complete -c {} -n '__fish_use_subcommand' -f -a '{}' -d '{}'\n",
+            bin, sub.name, sub.about.replace('\'', "\\'")
+        ));
+    }
+    for sub in subcommands {
+        let condition = format!("__fish_seen_subcommand_from {}", sub.name);
+        for flag in &sub.flags {
+            script.push_str(&fish_flag(bin, &condition, flag));
+        }
+    }
+    script
+}
+
+fn powershell_list(words: &[&str]) -> String {
+    words.iter().map(|word| format!("'{}'", word.replace('\'', "''"))).collect::<Vec<_>>().join(", ")
+}
+
+fn powershell(bin: &str, root: &CommandSpec, subcommands: &[CommandSpec]) -> String {
+    let mut script = format!("Register-ArgumentCompleter -Native -CommandName '{}' -ScriptBlock {{\n", bin);
+    script.push_str("    param($wordToComplete, $commandAst, $cursorPosition)\n\n");
+    
+    script.push_str("    $completions = @{\n");
+    let names: Vec<&str> = subcommands.iter().map(|sub| sub.name.as_str()).collect();
+    let root_words: Vec<&str> = root.flags.iter().flat_map(|flag| flag.names.iter().map(String::as_str)).chain(names.iter().copied()).collect();
+    script.push_str(&format!("        '' = @({})\n", powershell_list(&root_words)));
+    for sub in subcommands {
+        let words: Vec<&str> = sub.flags.iter().flat_map(|flag| flag.names.iter().map(String::as_str)).collect();
+        script.push_str(&format!("        '{}' = @({})\n", sub.name, powershell_list(&words)));
+    }
+    script.push_str("    }\n\n");
+    
+    script.push_str("    $values = @{\n");
+    for spec in std::iter::once(root).chain(subcommands) {
+        let prefix = if std::ptr::eq(spec, root) { "" } else { spec.name.as_str() };
+        for flag in spec.flags.iter().filter(|flag| !flag.values.is_empty()) {
+            let values: Vec<&str> = flag.values.iter().map(String::as_str).collect();
+            for name in &flag.names {
+                script.push_str(&format!("        '{} {}' = @({})\n", prefix, name, powershell_list(&values)));
+            }
+        }
+    }
+    script.push_str("    }\n\n");
+    
+    script.push_str(&format!(
+        "    $words = @($commandAst.CommandElements | Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }} | ForEach-Object {{ $_.ToString() }})\n    $command = ''\n    foreach ($word in $words | Select-Object -Skip 1) {{\n        if (@({}) -contains $word) {{ $command = $word; break }}\n    }}\n",
+        powershell_list(&names)
+    ));
+    script.push_str("    $previous = if ($words.Count -gt 0) { $words[-1] } else { '' }\n");
+    script.push_str("    $candidates = $values[\"$command $previous\"]\n");
+    script.push_str("    if (-not $candidates) { $candidates = $completions[$command] }\n");
+    script.push_str("    $candidates | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n");
+    script.push_str("        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)\n    }\n}\n");
+    script
+}
@@ -0,0 +1,120 @@
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{legacy_dir, AttError, Domain};
+
+/// Defaults read from `~/.mitre/config.toml`; command-line flags always take precedence.
+///
+/// Only flat `key = value` lines are understood, which is all the settings need:
+///
+/// ```toml
+/// domain = "mobile"
+/// color = false
+/// format = "table"
+/// references = false
+/// matrix_path = "/opt/attack/enterprise-attack.json"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    pub domain: Option<Domain>,
+    /// `false` turns colors off; `true` keeps them even when stdout is not a terminal
+    pub color: Option<bool>,
+    /// Default `--format` for commands that have one (text, table, markdown, json or yaml)
+    pub format: Option<String>,
+    /// `false` leaves the References list out of object details, like `--no-references`
+    pub references: Option<bool>,
+    pub matrix_path: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn path() -> Result<PathBuf, AttError> {
+        Ok(legacy_dir()?.join("config.toml"))
+    }
+    
+    /// Reads the config at `path`; a missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Config, AttError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Config::parse(&content)
+                .map_err(|message| AttError::InvalidConfig(path.to_path_buf(), message)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(AttError::Io(err)),
+        }
+    }
+    
+    pub fn parse(content: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fail = |message: String| format!("line {}: {}", number + 1, message);
+            let (key, value) = line.split_once('=').ok_or_else(|| fail("expected `key = value`".to_string()))?;
+            let value = value.trim();
+            
+            match key.trim() {
+                "domain" => {
+                    let name = parse_string(value).map_err(fail)?;
+                    let domain = Domain::from_str(&name, true).map_err(|_| fail(format!("unknown domain '{}'", name)))?;
+                    config.domain = Some(domain);
+                },
+                "color" => config.color = Some(parse_bool(value).map_err(fail)?),
+                "format" => config.format = Some(parse_string(value).map_err(fail)?),
+                "references" => config.references = Some(parse_bool(value).map_err(fail)?),
+                "matrix_path" => config.matrix_path = Some(PathBuf::from(parse_string(value).map_err(fail)?)),
+                other => return Err(fail(format!("unknown setting '{}'", other))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// Drops a trailing # comment, leaving any # inside a quoted string alone
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            },
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {},
+        }
+    }
+    line
+}
+
+fn parse_string(value: &str) -> Result<String, String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found {}", value))?;
+    
+    let mut parsed = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            parsed.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => parsed.push('"'),
+            Some('\\') => parsed.push('\\'),
+            Some('n') => parsed.push('\n'),
+            Some('t') => parsed.push('\t'),
+            other => return Err(format!("unsupported escape \\{}", other.map(String::from).unwrap_or_default())),
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, found {}", value)),
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{compare_mitre_ids, get_mitre_id, sort_objects, AttackData, AttackObject, SortOrder};
+
+pub struct BundleDiff<'a> {
+    pub added: Vec<&'a serde_json::Value>,
+    pub changed: Vec<&'a serde_json::Value>,
+    pub removed: Vec<&'a serde_json::Value>,
+}
+
+/// Matches objects across two bundles by STIX id; anything not byte-for-byte equal counts as changed
+pub fn diff_bundles<'a>(old: &'a [serde_json::Value], new: &'a [serde_json::Value]) -> BundleDiff<'a> {
+    let stix_id = |obj: &serde_json::Value| obj.get("id").and_then(|id| id.as_str()).map(str::to_string);
+    
+    let old_by_id: HashMap<String, &serde_json::Value> = old.iter()
+        .filter_map(|obj| stix_id(obj).map(|id| (id, obj)))
+        .collect();
+    let new_ids: HashSet<String> = new.iter().filter_map(stix_id).collect();
+    
+    let mut diff = BundleDiff { added: Vec::new(), changed: Vec::new(), removed: Vec::new() };
+    for obj in new {
+        let Some(id) = stix_id(obj) else { continue };
+        match old_by_id.get(&id) {
+            None => diff.added.push(obj),
+            Some(old_obj) if *old_obj != obj => diff.changed.push(obj),
+            Some(_) => {}
+        }
+    }
+    for obj in old {
+        if let Some(id) = stix_id(obj) && !new_ids.contains(&id) {
+            diff.removed.push(obj);
+        }
+    }
+    diff
+}
+
+/// What changed in the techniques and groups between two ATT&CK releases, each list by MITRE ID
+#[derive(Debug, Default)]
+pub struct ReleaseDiff<'a> {
+    /// In the new release under a MITRE ID the old one doesn't have
+    pub added: Vec<&'a AttackObject>,
+    /// In the old release under a MITRE ID the new one doesn't have
+    pub removed: Vec<&'a AttackObject>,
+    /// Deprecated in the new release but not in the old one
+    pub deprecated: Vec<&'a AttackObject>,
+    /// Revoked in the new release but not in the old one
+    pub revoked: Vec<&'a AttackObject>,
+    /// Current techniques whose `x_mitre_version` changed, as (old, new)
+    pub version_bumps: Vec<(&'a AttackObject, &'a AttackObject)>,
+}
+
+// Techniques and groups by upper-cased MITRE ID
+fn by_mitre_id(data: &AttackData) -> HashMap<String, &AttackObject> {
+    data.objects
+        .iter()
+        .filter(|obj| matches!(obj.obj_type.as_str(), "attack-pattern" | "intrusion-set"))
+        .filter_map(|obj| Some((get_mitre_id(obj)?.to_uppercase(), obj)))
+        .collect()
+}
+
+/// Compares two releases' techniques and groups by MITRE ID, for reviewing a matrix upgrade
+pub fn diff_releases<'a>(old: &'a AttackData, new: &'a AttackData) -> ReleaseDiff<'a> {
+    let old_ids = by_mitre_id(old);
+    let new_ids = by_mitre_id(new);
+    let mut diff = ReleaseDiff::default();
+    
+    for (mitre_id, &obj) in &new_ids {
+        let Some(&previous) = old_ids.get(mitre_id) else {
+            diff.added.push(obj);
+            continue;
+        };
+        if obj.is_deprecated() && !previous.is_deprecated() {
+            diff.deprecated.push(obj);
+        }
+        if obj.revoked == Some(true) && previous.revoked != Some(true) {
+            diff.revoked.push(obj);
+        }
+        if obj.obj_type == "attack-pattern"
+            && obj.revoked != Some(true)
+            && previous.version.is_some()
+            && obj.version.is_some()
+            && previous.version != obj.version {
+            diff.version_bumps.push((previous, obj));
+        }
+    }
+    diff.removed = old_ids.iter()
+        .filter(|(mitre_id, _)| !new_ids.contains_key(*mitre_id))
+        .map(|(_, &obj)| obj)
+        .collect();
+    
+    for list in [&mut diff.added, &mut diff.removed, &mut diff.deprecated, &mut diff.revoked] {
+        sort_objects(list, SortOrder::Id);
+    }
+    diff.version_bumps.sort_by(|(_, a), (_, b)| {
+        compare_mitre_ids(&get_mitre_id(a).unwrap_or_default(), &get_mitre_id(b).unwrap_or_default())
+    });
+    diff
+}
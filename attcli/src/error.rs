@@ -0,0 +1,54 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Everything that can go wrong while loading a matrix file
+#[derive(Debug)]
+pub enum AttError {
+    /// The matrix file does not exist at the resolved path
+    MatrixNotFound(PathBuf),
+    /// The file exists but could not be read
+    Io(std::io::Error),
+    /// The file is not valid JSON, or not shaped like an ATT&CK bundle
+    Parse(serde_json::Error),
+    /// The JSON parsed but has no top-level `objects` array
+    InvalidBundle(PathBuf),
+    /// The config file exists but has a line we can't understand
+    InvalidConfig(PathBuf, String),
+    /// No home directory to find the default matrix locations in, as in some containers
+    NoHomeDir,
+}
+
+impl fmt::Display for AttError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttError::MatrixNotFound(path) => write!(f, "MITRE ATT&CK matrix file not found at {}", path.display()),
+            AttError::Io(err) => write!(f, "could not read matrix file: {}", err),
+            AttError::Parse(err) => write!(f, "could not parse matrix file: {}", err),
+            AttError::InvalidBundle(path) => write!(f, "{} is not a STIX bundle (missing 'objects' array)", path.display()),
+            AttError::InvalidConfig(path, message) => write!(f, "invalid config file {}: {}", path.display(), message),
+            AttError::NoHomeDir => write!(f, "could not determine the home directory; pass --file or set ATTCLI_MATRIX (or XDG_DATA_HOME) to locate the matrix"),
+        }
+    }
+}
+
+impl std::error::Error for AttError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AttError::Io(err) => Some(err),
+            AttError::Parse(err) => Some(err),
+            AttError::MatrixNotFound(_) | AttError::InvalidBundle(_) | AttError::InvalidConfig(..) | AttError::NoHomeDir => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AttError {
+    fn from(err: std::io::Error) -> Self {
+        AttError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for AttError {
+    fn from(err: serde_json::Error) -> Self {
+        AttError::Parse(err)
+    }
+}
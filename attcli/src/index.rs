@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::{get_mitre_id, AttackObject};
+
+/// A relationship edge: the relationship object and the object at its other end,
+/// both as positions in `AttackData::objects`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Link {
+    pub relationship: usize,
+    pub object: usize,
+}
+
+/// Lookup tables built once after loading so commands don't rescan every object
+#[derive(Debug, Default, PartialEq)]
+pub struct Index {
+    pub(crate) by_id: HashMap<String, usize>,
+    pub(crate) by_mitre_id: HashMap<String, Vec<usize>>,
+    pub(crate) outgoing: HashMap<String, Vec<Link>>,
+    pub(crate) incoming: HashMap<String, Vec<Link>>,
+}
+
+impl Index {
+    /// Builds the tables on one thread, or on all cores with the `parallel` feature
+    pub fn build(objects: &[AttackObject]) -> Self {
+        #[cfg(feature = "parallel")]
+        return Index::build_parallel(objects);
+        #[cfg(not(feature = "parallel"))]
+        return Index::build_sequential(objects);
+    }
+    
+    pub fn build_sequential(objects: &[AttackObject]) -> Self {
+        let mut index = Index::default();
+        
+        for (i, obj) in objects.iter().enumerate() {
+            index.by_id.insert(obj.id.clone(), i);
+            if let Some(mitre_id) = get_mitre_id(obj) {
+                index.by_mitre_id.entry(mitre_id.to_uppercase()).or_default().push(i);
+            }
+        }
+        
+        for (i, obj) in objects.iter().enumerate() {
+            if obj.obj_type == "relationship"
+                && let (Some(source_ref), Some(target_ref)) = (&obj.source_ref, &obj.target_ref)
+                && let (Some(&source), Some(&target)) = (index.by_id.get(source_ref), index.by_id.get(target_ref)) {
+                index.outgoing.entry(source_ref.clone()).or_default().push(Link { relationship: i, object: target });
+                index.incoming.entry(target_ref.clone()).or_default().push(Link { relationship: i, object: source });
+            }
+        }
+        
+        index
+    }
+    
+    /// Same tables as `build_sequential`: threads only work out the keys and relationship ends,
+    /// which are then inserted in object order
+    #[cfg(feature = "parallel")]
+    pub fn build_parallel(objects: &[AttackObject]) -> Self {
+        let mut index = Index::default();
+        
+        let keys = map_chunks(objects, |obj| Some((obj.id.clone(), get_mitre_id(obj).map(|mitre_id| mitre_id.to_uppercase()))));
+        for (i, (id, mitre_id)) in keys {
+            index.by_id.insert(id, i);
+            if let Some(mitre_id) = mitre_id {
+                index.by_mitre_id.entry(mitre_id).or_default().push(i);
+            }
+        }
+        
+        let by_id = &index.by_id;
+        let ends = map_chunks(objects, |obj| {
+            if obj.obj_type != "relationship" {
+                return None;
+            }
+            Some((*by_id.get(obj.source_ref.as_ref()?)?, *by_id.get(obj.target_ref.as_ref()?)?))
+        });
+        for (i, (source, target)) in ends {
+            let obj = &objects[i];
+            if let (Some(source_ref), Some(target_ref)) = (&obj.source_ref, &obj.target_ref) {
+                index.outgoing.entry(source_ref.clone()).or_default().push(Link { relationship: i, object: target });
+                index.incoming.entry(target_ref.clone()).or_default().push(Link { relationship: i, object: source });
+            }
+        }
+        
+        index
+    }
+}
+
+// Runs `f` over the objects split into one chunk per core, returning the kept results with
+// their object positions, in object order
+#[cfg(feature = "parallel")]
+fn map_chunks<T: Send>(
+    objects: &[AttackObject],
+    f: impl Fn(&AttackObject) -> Option<T> + Sync,
+) -> Vec<(usize, T)> {
+    let threads = std::thread::available_parallelism().map_or(1, usize::from);
+    let chunk_size = objects.len().div_ceil(threads).max(1);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = objects.chunks(chunk_size)
+            .enumerate()
+            .map(|(n, chunk)| scope.spawn(move || {
+                chunk.iter()
+                    .enumerate()
+                    .filter_map(|(offset, obj)| {
+                        let i = n * chunk_size + offset;
+                        f(obj).map(|value| (i, value))
+                    })
+                    .collect::<Vec<_>>()
+            }))
+            .collect();
+        workers.into_iter()
+            .flat_map(|worker| worker.join().expect("index worker panicked"))
+            .collect()
+    })
+}
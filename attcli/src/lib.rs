@@ -0,0 +1,324 @@
+//! Parsing and lookup logic for the MITRE ATT&CK STIX bundle.
+//!
+//! The `attcli` binary is a thin layer of argument parsing and printing on top of this.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod cache;
+mod config;
+mod diff;
+mod error;
+mod index;
+mod query;
+mod validate;
+mod yaml;
+
+pub use cache::{cache_path, cache_path_in, read_cache, write_cache};
+pub use config::Config;
+pub use diff::{diff_bundles, diff_releases, BundleDiff, ReleaseDiff};
+pub use error::AttError;
+pub use index::{Index, Link};
+pub use query::*;
+pub use validate::{validate_bundle, Issue, Problem};
+pub use yaml::to_yaml;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AttackData {
+    pub objects: Vec<AttackObject>,
+    #[serde(skip)]
+    pub(crate) index: Index,
+}
+
+impl AttackData {
+    /// Objects carrying this MITRE ID (case-insensitive); revoked and current objects can share one
+    pub fn find_by_mitre_id<'a>(&'a self, mitre_id: &str) -> impl Iterator<Item = &'a AttackObject> + 'a {
+        self.index.by_mitre_id
+            .get(&mitre_id.to_uppercase())
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.objects[i])
+    }
+    
+    /// The object with this STIX id
+    pub fn get(&self, stix_id: &str) -> Option<&AttackObject> {
+        self.index.by_id.get(stix_id).map(|&i| &self.objects[i])
+    }
+    
+    /// Every relationship object with `id` as its source or target, whatever its type
+    pub fn relationships_of<'a>(&'a self, id: &str) -> impl Iterator<Item = &'a AttackObject> + 'a {
+        [self.index.outgoing.get(id), self.index.incoming.get(id)]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|link| &self.objects[link.relationship])
+    }
+    
+    /// Objects that `source_id` points at through relationships of the given type
+    pub fn related_targets<'a>(&'a self, source_id: &str, relationship_type: &'a str) -> impl Iterator<Item = &'a AttackObject> + 'a {
+        self.links(&self.index.outgoing, source_id, relationship_type).map(|(_, obj)| obj)
+    }
+    
+    /// Objects pointing at `target_id` through relationships of the given type
+    pub fn related_sources<'a>(&'a self, target_id: &str, relationship_type: &'a str) -> impl Iterator<Item = &'a AttackObject> + 'a {
+        self.related_source_links(target_id, relationship_type).map(|(_, obj)| obj)
+    }
+    
+    /// Like `related_sources`, but paired with the relationship object (for its description)
+    pub fn related_source_links<'a>(
+        &'a self,
+        target_id: &str,
+        relationship_type: &'a str,
+    ) -> impl Iterator<Item = (&'a AttackObject, &'a AttackObject)> + 'a {
+        self.links(&self.index.incoming, target_id, relationship_type)
+    }
+    
+    fn links<'a>(
+        &'a self,
+        adjacency: &'a HashMap<String, Vec<Link>>,
+        id: &str,
+        relationship_type: &'a str,
+    ) -> impl Iterator<Item = (&'a AttackObject, &'a AttackObject)> + 'a {
+        adjacency
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(|link| (&self.objects[link.relationship], &self.objects[link.object]))
+            .filter(move |(relationship, _)| relationship.relationship_type.as_deref() == Some(relationship_type))
+    }
+    
+    /// Whether the bundle belongs to `domain`; bundles without any `x_mitre_domains` get the benefit of the doubt
+    pub fn matches_domain(&self, domain: Domain) -> bool {
+        let mut tagged = self.objects.iter().filter_map(|obj| obj.domains.as_ref()).peekable();
+        tagged.peek().is_none() || tagged.flatten().any(|name| name == domain.stix_name())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AttackObject {
+    #[serde(rename = "type")]
+    pub obj_type: String,
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "external_references")]
+    pub external_references: Option<Vec<ExternalReference>>,
+    #[serde(rename = "kill_chain_phases")]
+    pub kill_chain_phases: Option<Vec<KillChainPhase>>,
+    pub aliases: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_aliases")]
+    pub software_aliases: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_platforms")]
+    pub platforms: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_tactics")]
+    pub tactics: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_shortname")]
+    pub shortname: Option<String>,
+    #[serde(rename = "x_mitre_version")]
+    pub version: Option<String>,
+    #[serde(rename = "x_mitre_domains")]
+    pub domains: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_is_subtechnique")]
+    pub is_subtechnique: Option<bool>,
+    #[serde(rename = "x_mitre_deprecated")]
+    pub deprecated: Option<bool>,
+    pub revoked: Option<bool>,
+    #[serde(rename = "x_mitre_detection")]
+    pub detection: Option<String>,
+    #[serde(rename = "x_mitre_data_sources")]
+    pub data_sources: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_effective_permissions")]
+    pub effective_permissions: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_permissions_required")]
+    pub permissions_required: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_system_requirements")]
+    pub system_requirements: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_defense_bypassed")]
+    pub defense_bypassed: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_remote_support")]
+    pub remote_support: Option<bool>,
+    #[serde(rename = "x_mitre_impact_type")]
+    pub impact_type: Option<Vec<String>>,
+    #[serde(rename = "x_mitre_contributors")]
+    pub contributors: Option<Vec<String>>,
+    /// On data components: the data source they belong to
+    #[serde(rename = "x_mitre_data_source_ref")]
+    pub data_source_ref: Option<String>,
+    /// On matrices: their tactics' STIX ids, in kill-chain order
+    pub tactic_refs: Option<Vec<String>>,
+    /// On locations, or as an `x_mitre_` origin hint on groups
+    #[serde(alias = "x_mitre_country")]
+    pub country: Option<String>,
+    #[serde(alias = "x_mitre_region")]
+    pub region: Option<String>,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// RFC 3339 timestamps, e.g. "2023-09-01T00:00:00.000Z"
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    #[serde(rename = "source_ref")]
+    pub source_ref: Option<String>,
+    #[serde(rename = "target_ref")]
+    pub target_ref: Option<String>,
+    #[serde(rename = "relationship_type")]
+    pub relationship_type: Option<String>,
+}
+
+impl AttackObject {
+    /// Flagged `x_mitre_deprecated`: kept in the bundle but no longer maintained by MITRE
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated == Some(true)
+    }
+    
+    /// Deprecated or revoked: MITRE has sunset the object, whether or not it names a replacement
+    pub fn is_sunset(&self) -> bool {
+        self.is_deprecated() || self.revoked == Some(true)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExternalReference {
+    pub source_name: String,
+    pub external_id: Option<String>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct KillChainPhase {
+    pub kill_chain_name: String,
+    pub phase_name: String,
+}
+
+impl KillChainPhase {
+    /// True for the ATT&CK kill chains of every domain (`mitre-attack`, `mitre-mobile-attack`, `mitre-ics-attack`)
+    pub fn is_attack(&self) -> bool {
+        matches!(self.kill_chain_name.as_str(), "mitre-attack" | "mitre-mobile-attack" | "mitre-ics-attack")
+    }
+}
+
+/// The separately published ATT&CK matrices
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Domain {
+    #[default]
+    Enterprise,
+    Mobile,
+    Ics,
+}
+
+impl Domain {
+    pub const ALL: [Domain; 3] = [Domain::Enterprise, Domain::Mobile, Domain::Ics];
+    
+    /// Name used in `x_mitre_domains`, Navigator layers and the published file names
+    pub fn stix_name(self) -> &'static str {
+        match self {
+            Domain::Enterprise => "enterprise-attack",
+            Domain::Mobile => "mobile-attack",
+            Domain::Ics => "ics-attack",
+        }
+    }
+    
+    pub fn file_name(self) -> String {
+        format!("{}.json", self.stix_name())
+    }
+    
+    /// Where MITRE publishes the current bundle for this domain
+    pub fn download_url(self) -> String {
+        format!("https://raw.githubusercontent.com/mitre-attack/attack-stix-data/master/{0}/{0}.json", self.stix_name())
+    }
+}
+
+/// Where matrices are kept: `$XDG_DATA_HOME/attcli`, else `~/.local/share/attcli`
+pub fn data_dir() -> Result<PathBuf, AttError> {
+    data_dir_in(dirs::home_dir(), std::env::var_os("XDG_DATA_HOME").map(PathBuf::from))
+}
+
+/// `data_dir` for the given home directory and `$XDG_DATA_HOME`; the spec says to ignore a relative one
+pub fn data_dir_in(home: Option<PathBuf>, xdg_data_home: Option<PathBuf>) -> Result<PathBuf, AttError> {
+    xdg_data_home
+        .filter(|path| path.is_absolute())
+        .or_else(|| Some(home?.join(".local").join("share")))
+        .map(|data_home| data_home.join("attcli"))
+        .ok_or(AttError::NoHomeDir)
+}
+
+/// `~/.mitre`, where older installs keep their matrices; it still holds `config.toml`
+pub fn legacy_dir() -> Result<PathBuf, AttError> {
+    dirs::home_dir().map(|home| home.join(".mitre")).ok_or(AttError::NoHomeDir)
+}
+
+/// Every path the domain's matrix is looked for at, in order: `<domain>-attack.json` in the data
+/// directory, then in `~/.mitre`; enterprise also accepts the `matrix.json` of older installs
+pub fn matrix_candidates(domain: Domain) -> Result<Vec<PathBuf>, AttError> {
+    let mut names = vec![domain.file_name()];
+    if domain == Domain::Enterprise {
+        names.push("matrix.json".to_string());
+    }
+    let dirs: Vec<PathBuf> = std::iter::once(data_dir()?).chain(legacy_dir().ok()).collect();
+    Ok(dirs.iter().flat_map(|dir| names.iter().map(move |name| dir.join(name))).collect())
+}
+
+/// The first of the domain's `matrix_candidates` that exists, else where a new one belongs (the data directory)
+pub fn get_domain_matrix_path(domain: Domain) -> Result<PathBuf, AttError> {
+    let mut candidates = matrix_candidates(domain)?;
+    let found = candidates.iter().position(|path| path.exists()).unwrap_or(0);
+    Ok(candidates.swap_remove(found))
+}
+
+/// Picks the matrix file: --file first, then an explicit --domain, then $ATTCLI_MATRIX, then the
+/// config file's `matrix_path` and `domain`, then the enterprise matrix. Only the default
+/// locations need a home directory.
+pub fn resolve_matrix_path(file: Option<&Path>, domain: Option<Domain>, config: &Config) -> Result<PathBuf, AttError> {
+    if let Some(file) = file {
+        return Ok(file.to_path_buf());
+    }
+    if let Some(domain) = domain {
+        return get_domain_matrix_path(domain);
+    }
+    std::env::var_os("ATTCLI_MATRIX")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| config.matrix_path.clone())
+        .map_or_else(|| get_domain_matrix_path(config.domain.unwrap_or_default()), Ok)
+}
+
+fn read_matrix_file(path: &Path) -> Result<String, AttError> {
+    fs::read_to_string(path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => AttError::MatrixNotFound(path.to_path_buf()),
+        _ => AttError::Io(err),
+    })
+}
+
+pub fn load_attack_data(path: &Path) -> Result<AttackData, AttError> {
+    parse_attack_data(&read_matrix_file(path)?)
+}
+
+/// Like `load_attack_data`, for a bundle that is already in memory
+pub fn parse_attack_data(content: &str) -> Result<AttackData, AttError> {
+    let mut data: AttackData = serde_json::from_str(content)?;
+    data.index = Index::build(&data.objects);
+    Ok(data)
+}
+
+/// Reads a bundle's objects untyped, so fields we don't model survive a round trip
+pub fn load_raw_objects(path: &Path) -> Result<Vec<serde_json::Value>, AttError> {
+    let content = read_matrix_file(path)?;
+    let mut bundle: serde_json::Value = serde_json::from_str(&content)?;
+    match bundle.get_mut("objects").map(serde_json::Value::take) {
+        Some(serde_json::Value::Array(objects)) => Ok(objects),
+        _ => Err(AttError::InvalidBundle(path.to_path_buf())),
+    }
+}
+
+pub fn get_mitre_id(obj: &AttackObject) -> Option<String> {
+    if let Some(refs) = &obj.external_references {
+        for ref_obj in refs {
+            if ref_obj.source_name == "mitre-attack" {
+                return ref_obj.external_id.clone();
+            }
+        }
+    }
+    None
+}
@@ -1,9 +1,16 @@
-use clap::{Parser, Subcommand};
+mod completions;
+
+use attcli::*;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use colored::*;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Parser)]
 #[command(name = "attcli")]
@@ -11,566 +18,4244 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Path to the ATT&CK matrix JSON (overrides --domain, ATTCLI_MATRIX and the default locations)
+    #[arg(long, global = true, value_name = "PATH")]
+    file: Option<PathBuf>,
+    /// Which ATT&CK matrix to read, <domain>-attack.json in the data directory [default: enterprise]
+    #[arg(long, global = true, value_enum)]
+    domain: Option<Domain>,
+    /// Print matching objects as JSON instead of formatted text
+    #[arg(long, global = true)]
+    json: bool,
+    /// Print one JSON object per line (NDJSON) from listings, search, recent and export-csv
+    #[arg(long, global = true, conflicts_with = "json")]
+    json_lines: bool,
+    /// Show revoked techniques and groups as-is instead of their replacements
+    #[arg(long, global = true)]
+    no_follow_revoked: bool,
+    /// Disable colored output (also honors NO_COLOR and non-terminal stdout)
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Replace (Citation: ...) markers with numbered footnotes listing the reference URLs
+    #[arg(long, global = true)]
+    resolve_citations: bool,
+    /// Parse the matrix JSON even when an up-to-date cache exists (the cache is not refreshed)
+    #[arg(long, global = true)]
+    no_cache: bool,
+    /// Keep deprecated groups, software and techniques in listings and search results
+    #[arg(long, global = true)]
+    include_deprecated: bool,
+    /// Wrap descriptions at this many columns instead of the terminal width (0 disables wrapping)
+    #[arg(long, global = true, value_name = "N")]
+    width: Option<usize>,
+    /// Print long output directly instead of through $PAGER
+    #[arg(long, global = true)]
+    no_pager: bool,
+    /// Write the output to this file instead of stdout, without colors (missing directories are created)
+    #[arg(short, long, global = true, value_name = "PATH")]
+    output: Option<PathBuf>,
+    /// Truncate procedure examples and mitigation guidance to this many characters (0 shows them in full)
+    #[arg(long, global = true, value_name = "CHARS", default_value_t = DEFAULT_PROCEDURE_LENGTH)]
+    procedure_length: usize,
+    /// Show only the first sentence of technique and group descriptions, keeping the other sections
+    #[arg(long, global = true)]
+    brief: bool,
+    /// Leave the References list out of object details (also `references = false` in the config file)
+    #[arg(long, global = true)]
+    no_references: bool,
+    /// Print reference URLs in full instead of as clickable terminal hyperlinks
+    #[arg(long, global = true)]
+    no_hyperlinks: bool,
+}
+
+// Procedure examples can run to several paragraphs; keep the technique view scannable
+const DEFAULT_PROCEDURE_LENGTH: usize = 200;
+
+// Presentation switches shared by the print_* functions
+#[derive(Clone, Debug, Default)]
+struct DisplayOptions {
+    resolve_citations: bool,
+    // Only show techniques available on one of these platforms (all of them with PlatformMatch::All); empty means no filter
+    platforms: Vec<String>,
+    platform_match: PlatformMatch,
+    // Characters of procedure text and mitigation guidance to show under a technique; 0 shows it in full
+    procedure_length: usize,
+    // Column to word-wrap descriptions at; 0 leaves them unwrapped
+    width: usize,
+    // Cut technique and group descriptions down to their first sentence
+    brief: bool,
+    // Order of technique listings
+    sort: SortOrder,
+    // Also list the techniques of the software a group uses
+    transitive: bool,
+    // Order of the tactic sections in "Used Techniques"; kill-chain order numbers them
+    tactic_order: TacticOrder,
+    // Techniques shown per tactic in "Used Techniques"; 0 shows all
+    technique_limit: usize,
+    // Mark the techniques in "Used Techniques" that run on this platform
+    highlight_platform: Option<String>,
+    // Which techniques "Total Techniques" counts
+    technique_count: TechniqueCount,
+    // Query whose match to emphasize in technique names
+    highlight: Option<String>,
+    // Leave out the References list (cited references are still listed)
+    hide_references: bool,
+    // List the group's software and campaigns before its techniques (`profile`)
+    profile: bool,
+    // Print references as OSC 8 terminal hyperlinks instead of raw URLs
+    hyperlinks: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// List all APT (Advanced Persistent Threat) groups
     #[command(name = "apt-list")]
-    AptList,
+    AptList {
+        /// Order groups by name, by MITRE ID or by how many techniques they use
+        #[arg(long, value_enum, default_value_t = GroupSort::Name)]
+        sort: GroupSort,
+        /// How to show each group's aliases
+        #[arg(long, value_enum, default_value_t = AliasDisplay::Inline)]
+        aliases: AliasDisplay,
+        /// Show at most N results (0 shows all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Print only the number of groups; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+        /// Only list deprecated and revoked groups, with what replaced the revoked ones
+        #[arg(long)]
+        deprecated_only: bool,
+    },
     /// Show information about a specific APT group
     #[command(name = "apt")]
-    Apt { name: String },
+    Apt {
+        name: String,
+        /// Match the whole name or an alias, ignoring case, instead of any part of it
+        #[arg(long)]
+        exact: bool,
+        /// With --exact, fall back to matching part of the name when nothing matches exactly
+        #[arg(long, requires = "exact")]
+        fuzzy: bool,
+        /// Only show techniques for this platform (repeatable, e.g. --platform Windows)
+        #[arg(long = "platform")]
+        platforms: Vec<String>,
+        /// With several --platform values, whether a technique needs any or all of them
+        #[arg(long, value_enum, default_value_t = PlatformMatch::Any)]
+        platform_match: PlatformMatch,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<GroupFormat>,
+        /// Order techniques within each tactic by name or by MITRE ID
+        #[arg(long, value_enum, default_value_t = SortOrder::Name)]
+        sort: SortOrder,
+        /// Order the tactic sections alphabetically or as an intrusion progresses
+        #[arg(long, value_enum, default_value_t = TacticOrder::Killchain)]
+        tactic_order: TacticOrder,
+        /// Show at most N techniques per tactic (0 shows all); the total still counts every technique.
+        /// Only the text, markdown and html formats list techniques by tactic
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        limit_techniques: usize,
+        /// Maximum table width for --format table; defaults to the terminal width, 0 disables truncation
+        #[arg(long)]
+        wrap_width: Option<usize>,
+        /// Mark techniques available on this platform in red instead of filtering out the rest
+        #[arg(long, value_name = "PLATFORM")]
+        highlight_platform: Option<String>,
+        /// Which techniques the total (and --count-only) counts: all, only parents or only sub-techniques
+        #[arg(long, value_enum, default_value_t = TechniqueCount::All)]
+        count: TechniqueCount,
+        /// Also list techniques the group gets through the malware and tools it uses
+        #[arg(long)]
+        transitive: bool,
+        /// Instead of the profile, rank the mitigations for the group's techniques by how many each covers
+        #[arg(long, conflicts_with_all = ["format", "short"])]
+        mitigations: bool,
+        /// Instead of the profile, rank the data sources that could detect the group's techniques by how many each covers
+        #[arg(long, conflicts_with_all = ["format", "short", "mitigations"])]
+        data_sources: bool,
+        /// One line per match: ID, name, first sentence of the description and platforms
+        #[arg(long, conflicts_with = "format")]
+        short: bool,
+        /// Print only the number of techniques the matching groups use (after --platform); the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
     /// Show information about a technique by ID (e.g., T1055)
     #[command(name = "tid")]
-    TechniqueId { id: String },
+    TechniqueId {
+        id: String,
+        /// Only show techniques for this platform (repeatable, e.g. --platform Windows)
+        #[arg(long = "platform")]
+        platforms: Vec<String>,
+        /// With several --platform values, whether a technique needs any or all of them
+        #[arg(long, value_enum, default_value_t = PlatformMatch::Any)]
+        platform_match: PlatformMatch,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<ObjectFormat>,
+        /// After the technique, show each of its sub-techniques in full
+        #[arg(long)]
+        with_subs: bool,
+        /// One line per match: ID, name, first sentence of the description and platforms
+        #[arg(long, conflicts_with = "format")]
+        short: bool,
+        /// Print only the technique's tactics (phase names), one per line; the exit status is 1 when it isn't found
+        #[arg(long, conflicts_with_all = ["format", "with_subs", "short"])]
+        tactics_only: bool,
+        /// List every technique whose ID starts with ID (e.g. T10); an ID ending in "." always does
+        #[arg(long)]
+        prefix: bool,
+    },
     /// Show information about a technique by name
     #[command(name = "tn")]
-    TechniqueName { name: String },
+    TechniqueName {
+        name: String,
+        /// Match the whole name, ignoring case, instead of any part of it
+        #[arg(long)]
+        exact: bool,
+        /// With --exact, fall back to matching part of the name when nothing matches exactly
+        #[arg(long, requires = "exact")]
+        fuzzy: bool,
+        /// Keep duplicate matches of the same object (for debugging)
+        #[arg(long)]
+        no_dedupe: bool,
+        /// Show at most N results (0 shows all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Only show techniques for this platform (repeatable, e.g. --platform Windows)
+        #[arg(long = "platform")]
+        platforms: Vec<String>,
+        /// With several --platform values, whether a technique needs any or all of them
+        #[arg(long, value_enum, default_value_t = PlatformMatch::Any)]
+        platform_match: PlatformMatch,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<ObjectFormat>,
+        /// One line per match: ID, name, first sentence of the description and platforms
+        #[arg(long, conflicts_with = "format")]
+        short: bool,
+        /// Print only the number of matching techniques; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// List all tactics in kill-chain order
+    #[command(name = "tactic-list")]
+    TacticList {
+        /// Print only the number of tactics; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
     /// Show information about a tactic (e.g., persistence, privilege-escalation)
     #[command(name = "tactic")]
-    Tactic { name: String },
+    Tactic {
+        name: String,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<ObjectFormat>,
+        /// List the techniques per tactic in kill-chain order, with sub-techniques nested under their parent
+        #[arg(long)]
+        tree: bool,
+    },
+    /// List techniques by MITRE ID, optionally only those of one tactic or platform
+    #[command(name = "technique-list")]
+    TechniqueList {
+        /// Only list techniques of this tactic (name or shortname, e.g. privilege-escalation)
+        #[arg(long)]
+        tactic: Option<String>,
+        /// Only list techniques for this platform (repeatable, e.g. --platform Windows)
+        #[arg(long = "platform")]
+        platforms: Vec<String>,
+        /// With several --platform values, whether a technique needs any or all of them
+        #[arg(long, value_enum, default_value_t = PlatformMatch::Any)]
+        platform_match: PlatformMatch,
+        /// Also list sub-techniques
+        #[arg(long)]
+        include_subs: bool,
+        /// Print only the number of techniques; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+        /// Only list deprecated and revoked techniques, with what replaced the revoked ones
+        #[arg(long)]
+        deprecated_only: bool,
+    },
+    /// List all software (malware and tools)
+    #[command(name = "software-list")]
+    SoftwareList {
+        /// Order software by name or by MITRE ID
+        #[arg(long, value_enum, default_value_t = SortOrder::Name)]
+        sort: SortOrder,
+        /// Show at most N results (0 shows all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Print only the number of malware and tools; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+        /// Only list deprecated and revoked software, with what replaced the revoked ones
+        #[arg(long)]
+        deprecated_only: bool,
+    },
+    /// Show information about malware or a tool by ID (e.g., S0002) or name
+    #[command(name = "software")]
+    Software {
+        id_or_name: String,
+        /// One line per match: ID, name, first sentence of the description and platforms
+        #[arg(long)]
+        short: bool,
+    },
+    /// List all campaigns
+    #[command(name = "campaign-list")]
+    CampaignList {
+        /// Print only the number of campaigns; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// Show information about a campaign by ID (e.g., C0001) or name
+    #[command(name = "campaign")]
+    Campaign { id_or_name: String },
+    /// Show information about a mitigation by ID (e.g., M1040)
+    #[command(name = "mitigation")]
+    Mitigation {
+        id: String,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<ObjectFormat>,
+        /// List the techniques of the tactics it addresses that the mitigation leaves uncovered
+        #[arg(long, conflicts_with = "format")]
+        gaps: bool,
+    },
+    /// List the techniques, per tactic, missing from a list of covered technique IDs
+    #[command(name = "gaps")]
+    Gaps {
+        /// File with one covered technique ID per line (blank lines and # comments are skipped)
+        covered_file: PathBuf,
+        /// Only analyze this tactic (e.g., persistence)
+        #[arg(long)]
+        tactic: Option<String>,
+    },
+    /// Rank groups by how many of the observed techniques they are known to use
+    #[command(name = "attribute")]
+    Attribute {
+        /// Observed technique IDs (e.g., T1055 T1566.001)
+        techniques: Vec<String>,
+        /// File with one technique ID per line (blank lines and # comments are skipped)
+        #[arg(long, value_name = "PATH")]
+        techniques_file: Option<PathBuf>,
+        /// Show at most this many groups
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// List the techniques used by the most groups, to prioritize defenses
+    #[command(name = "top-techniques")]
+    TopTechniques {
+        /// Show at most this many techniques
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Only rank techniques of this tactic (e.g., persistence)
+        #[arg(long)]
+        tactic: Option<String>,
+    },
+    /// List the groups with the most documented techniques
+    #[command(name = "top-groups")]
+    TopGroups {
+        /// Show at most this many groups
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Only count techniques of this tactic (e.g., persistence)
+        #[arg(long)]
+        tactic: Option<String>,
+    },
+    /// Show a group's full dossier: details, software, campaigns and techniques
+    #[command(name = "profile")]
+    Profile {
+        /// Group name, alias or MITRE ID
+        group: String,
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<ProfileFormat>,
+    },
+    /// Show the techniques two groups have in common and how similar their TTPs are
+    #[command(name = "compare")]
+    Compare { group_a: String, group_b: String },
+    /// Search techniques' detection guidance (e.g., "Sysmon" or "command line")
+    #[command(name = "search-detection")]
+    SearchDetection { query: String },
+    /// List the techniques a data source can observe (e.g., "Process" or "Process: Process Creation")
+    #[command(name = "data-source")]
+    DataSource { name: String },
+    /// Open an object's attack.mitre.org page in the default browser (e.g., T1055, G0007)
+    #[command(name = "open")]
+    Open { id: String },
+    /// Print the type and name of the object with a MITRE ID or STIX id; the exit status is 1 when there is none
+    #[command(name = "which")]
+    Which { id: String },
+    /// List every relationship touching an object, by STIX id or MITRE ID
+    #[command(name = "relationships")]
+    Relationships { id: String },
+    /// List all mitigations with the number of techniques each one covers
+    #[command(name = "list-mitigations", visible_alias = "mitigation-list")]
+    MitigationList {
+        /// Output format [default: text, or `format` from the config file]
+        #[arg(long, value_enum)]
+        format: Option<OutputFormat>,
+        /// Sort order for the listing
+        #[arg(long, value_enum, default_value_t = MitigationSort::Id)]
+        sort: MitigationSort,
+        /// Show at most N results (0 shows all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Maximum table width; defaults to the terminal width, 0 disables truncation
+        #[arg(long)]
+        wrap_width: Option<usize>,
+        /// Print only the number of mitigations; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// Search names, aliases and descriptions across all object types
+    #[command(name = "search")]
+    Search {
+        /// Words that must all appear, in any order, in a name, alias or description
+        query: String,
+        /// Match objects containing any of the words instead of all of them
+        #[arg(long)]
+        any: bool,
+        /// Only search one kind of object
+        #[arg(long = "type", value_enum)]
+        kind: Option<ObjectKind>,
+        /// Show at most N results (0 shows all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Print only the number of matches; the exit status is 1 when there are none
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// List techniques, groups, software, mitigations and tactics modified since a date, newest first
+    #[command(name = "recent")]
+    Recent {
+        /// Earliest modification date to include, as YYYY-MM-DD [default: 90 days ago]
+        #[arg(long, value_parser = parse_date)]
+        since: Option<String>,
+    },
+    /// Generate an ATT&CK Navigator layer for a group's techniques
+    #[command(name = "navigator-layer")]
+    NavigatorLayer {
+        group: String,
+    },
+    /// Write the matrix, or only what changed since an older one, as a STIX bundle
+    #[command(name = "export")]
+    Export {
+        /// Only export objects added or changed since this older matrix file
+        #[arg(long, value_name = "OLD_MATRIX")]
+        since_diff: Option<PathBuf>,
+        /// Export a techniques × groups usage matrix as CSV instead of a bundle
+        #[arg(long, conflicts_with = "since_diff")]
+        group_technique_matrix: bool,
+        /// Comma-separated groups (name, alias or ID) to use as matrix columns
+        #[arg(long, value_delimiter = ',', requires = "group_technique_matrix")]
+        groups: Vec<String>,
+        /// Maximum number of group columns in the matrix
+        #[arg(long, default_value_t = DEFAULT_MATRIX_COLUMNS, requires = "group_technique_matrix")]
+        max_columns: usize,
+    },
+    /// Compare two matrix files: techniques and groups added, removed, deprecated, revoked or re-versioned
+    #[command(name = "diff")]
+    Diff {
+        old_file: PathBuf,
+        new_file: PathBuf,
+    },
+    /// Write every technique as a CSV row (ID, name, tactics, platforms, data sources, flags, description)
+    #[command(name = "export-csv")]
+    ExportCsv {
+        /// File to write the CSV to (same as --output)
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Write the group-technique `uses` graph in Graphviz DOT format (render with dot -Tpng)
+    #[command(name = "export-dot")]
+    ExportDot {
+        /// Only this group (name, alias or ID) and the techniques it uses
+        #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+        group: Option<String>,
+        /// Include every group
+        #[arg(long)]
+        all: bool,
+    },
+    /// Time a fixed lookup workload against the loaded matrix
+    #[command(name = "benchmark", hide = true)]
+    Benchmark {
+        /// Number of random technique lookups to perform
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        lookups: usize,
+    },
+    /// Delete the parsed-matrix cache kept for the matrix file
+    #[command(name = "cache-clear")]
+    CacheClear,
+    /// Download the current matrix for --domain from MITRE's attack-stix-data repository
+    #[command(name = "update")]
+    Update {
+        /// Put back the matrix the last update replaced
+        #[arg(long)]
+        rollback: bool,
+    },
+    /// Check the matrix for dangling relationships, duplicate IDs and other signs of a broken download
+    #[command(name = "validate")]
+    Validate,
+    /// Print the attcli version, the ATT&CK release of the matrix and when it was last modified
+    #[command(name = "version")]
+    Version,
+    /// Print where the config file is read from
+    #[command(name = "config-path")]
+    ConfigPath,
+    /// Print a shell completion script (e.g. attcli completions zsh > _attcli)
+    #[command(name = "completions", hide = true)]
+    Completions {
+        shell: completions::Shell,
+    },
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct AttackData {
-    objects: Vec<AttackObject>,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Table,
+    Markdown,
+    Json,
+    Yaml,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct AttackObject {
-    #[serde(rename = "type")]
-    obj_type: String,
-    id: String,
-    name: Option<String>,
-    description: Option<String>,
-    #[serde(rename = "external_references")]
-    external_references: Option<Vec<ExternalReference>>,
-    #[serde(rename = "kill_chain_phases")]
-    kill_chain_phases: Option<Vec<KillChainPhase>>,
-    aliases: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_platforms")]
-    platforms: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_tactics")]
-    tactics: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_shortname")]
-    shortname: Option<String>,
-    #[serde(rename = "x_mitre_version")]
-    version: Option<String>,
-    #[serde(rename = "x_mitre_deprecated")]
-    deprecated: Option<bool>,
-    #[serde(rename = "x_mitre_detection")]
-    detection: Option<String>,
-    #[serde(rename = "x_mitre_data_sources")]
-    data_sources: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_effective_permissions")]
-    effective_permissions: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_permissions_required")]
-    permissions_required: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_system_requirements")]
-    system_requirements: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_defense_bypassed")]
-    defense_bypassed: Option<Vec<String>>,
-    #[serde(rename = "x_mitre_remote_support")]
-    remote_support: Option<bool>,
-    #[serde(rename = "x_mitre_impact_type")]
-    impact_type: Option<Vec<String>>,
-    #[serde(rename = "source_ref")]
-    source_ref: Option<String>,
-    #[serde(rename = "target_ref")]
-    target_ref: Option<String>,
-    #[serde(rename = "relationship_type")]
-    relationship_type: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct ExternalReference {
-    source_name: String,
-    external_id: Option<String>,
-    url: Option<String>,
-    description: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct KillChainPhase {
-    kill_chain_name: String,
-    phase_name: String,
-}
-
-fn get_matrix_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".mitre").join("matrix.json")
-}
-
-fn load_attack_data() -> Result<AttackData, Box<dyn std::error::Error>> {
-    let path = get_matrix_path();
-    if !path.exists() {
-        eprintln!("{}", "Error: MITRE ATT&CK matrix file not found at ~/.mitre/matrix.json".red());
-        eprintln!("{}", "Please run the installation script first.".yellow());
-        std::process::exit(1);
-    }
-
-    let content = fs::read_to_string(path)?;
-    let data: AttackData = serde_json::from_str(&content)?;
-    Ok(data)
-}
-
-fn get_mitre_id(obj: &AttackObject) -> Option<String> {
-    if let Some(refs) = &obj.external_references {
-        for ref_obj in refs {
-            if ref_obj.source_name == "mitre-attack" {
-                return ref_obj.external_id.clone();
-            }
+// --format for commands that show whole objects, where only text or serialized output makes sense
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ObjectFormat {
+    Text,
+    Json,
+    Yaml,
+}
+
+impl ObjectFormat {
+    // --json wins over --format, which wins over the config file's `format` when that applies here
+    fn resolve(json: bool, format: Option<ObjectFormat>, default_format: OutputFormat) -> ObjectFormat {
+        if json {
+            return ObjectFormat::Json;
         }
+        format.unwrap_or(match default_format {
+            OutputFormat::Json => ObjectFormat::Json,
+            OutputFormat::Yaml => ObjectFormat::Yaml,
+            OutputFormat::Text | OutputFormat::Table | OutputFormat::Markdown => ObjectFormat::Text,
+        })
     }
-    None
 }
 
-fn get_related_techniques<'a>(group_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
-    let mut related_technique_ids = Vec::new();
-    
-    // Find all relationships where this group is the source and targets attack-patterns
-    for obj in &data.objects {
-        if obj.obj_type == "relationship" {
-            if let (Some(source_ref), Some(target_ref), Some(relationship_type)) = 
-                (&obj.source_ref, &obj.target_ref, &obj.relationship_type) {
-                if source_ref == group_id && relationship_type == "uses" {
-                    related_technique_ids.push(target_ref.as_str());
-                }
-            }
+// --format for `profile`: the text dossier, an exportable Markdown one, or everything as one JSON object
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ProfileFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+impl ProfileFormat {
+    fn resolve(json: bool, format: Option<ProfileFormat>, default_format: OutputFormat) -> ProfileFormat {
+        if json {
+            return ProfileFormat::Json;
         }
+        format.unwrap_or(match default_format {
+            OutputFormat::Markdown => ProfileFormat::Markdown,
+            OutputFormat::Json => ProfileFormat::Json,
+            OutputFormat::Text | OutputFormat::Table | OutputFormat::Yaml => ProfileFormat::Text,
+        })
     }
-    
-    // Get the actual technique objects
-    let mut techniques = Vec::new();
-    for obj in &data.objects {
-        if obj.obj_type == "attack-pattern" && related_technique_ids.contains(&obj.id.as_str()) {
-            techniques.push(obj);
+}
+
+// --format for `apt`: every OutputFormat, plus a Mermaid diagram of the group's techniques and
+// a self-contained HTML report
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum GroupFormat {
+    Text,
+    Table,
+    Markdown,
+    Json,
+    Yaml,
+    Mermaid,
+    Html,
+}
+
+impl GroupFormat {
+    fn resolve(json: bool, format: Option<GroupFormat>, default_format: OutputFormat) -> GroupFormat {
+        if json {
+            return GroupFormat::Json;
         }
+        format.unwrap_or(match default_format {
+            OutputFormat::Text => GroupFormat::Text,
+            OutputFormat::Table => GroupFormat::Table,
+            OutputFormat::Markdown => GroupFormat::Markdown,
+            OutputFormat::Json => GroupFormat::Json,
+            OutputFormat::Yaml => GroupFormat::Yaml,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum GroupSort {
+    /// Alphabetically by name
+    Name,
+    /// By MITRE ID (G-code)
+    Id,
+    /// By number of techniques used, most first
+    TechniqueCount,
+}
+
+// --aliases for `apt-list`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum AliasDisplay {
+    /// Every alias on a line under the group
+    Inline,
+    /// No aliases
+    Hidden,
+    /// Only how many aliases the group has
+    Count,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum MitigationSort {
+    /// By MITRE ID (M-code)
+    Id,
+    /// Alphabetically by name
+    Name,
+    /// By number of techniques mitigated, most first
+    Coverage,
+}
+
+#[derive(Debug, Serialize)]
+struct MitigationSummary {
+    id: String,
+    name: String,
+    techniques: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchmarkTiming {
+    name: String,
+    iterations: usize,
+    total_ms: f64,
+    mean_us: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchmarkReport {
+    objects: usize,
+    timings: Vec<BenchmarkTiming>,
+}
+
+// Name lookup for `apt` and `tn`: substring matching by default; with --exact only whole-name
+// matches, falling back to substring matching when there are none only if --fuzzy is also given
+fn match_names<'a>(
+    exact: bool,
+    fuzzy: bool,
+    exact_matches: impl FnOnce() -> Vec<&'a AttackObject>,
+    substring_matches: impl FnOnce() -> Vec<&'a AttackObject>,
+) -> Vec<&'a AttackObject> {
+    if !exact {
+        return substring_matches();
+    }
+    let found = exact_matches();
+    if found.is_empty() && fuzzy {
+        substring_matches()
+    } else {
+        found
+    }
+}
+
+// An exact name, alias or ID first, then a partial name as long as it picks out one group
+fn find_single_group<'a>(query: &str, data: &'a AttackData) -> Result<&'a AttackObject, String> {
+    if let Some(group) = find_group(query, data) {
+        return Ok(group);
+    }
+    match find_groups_by_name(query, data).as_slice() {
+        [group] => Ok(group),
+        [] => Err(format!("No APT group found matching '{}'", query)),
+        _ => Err(format!("'{}' matches several groups; use the exact name or MITRE ID", query)),
+    }
+}
+
+fn write_output(content: &str, output: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        Some(path) => {
+            create_parent_dirs(path)?;
+            fs::write(path, content)?;
+        },
+        None => println!("{}", content.trim_end()),
     }
+    Ok(())
+}
+
+fn create_parent_dirs(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent),
+        _ => Ok(()),
+    }
+}
+
+// Commands that write their result through write_output, and so honor --output themselves
+fn writes_own_output(command: &Commands) -> bool {
+    matches!(command, Commands::NavigatorLayer { .. } | Commands::Export { .. } | Commands::ExportCsv { .. } | Commands::ExportDot { .. })
+}
+
+// Points stdout at `path`, so everything a command prints ends up in the file
+#[cfg(unix)]
+fn redirect_stdout(path: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
     
+    create_parent_dirs(path)?;
+    let file = fs::File::create(path)?;
+    // SAFETY: both descriptors are open; dup2 only makes STDOUT_FILENO refer to the file
+    if unsafe { libc::dup2(file.as_raw_fd(), libc::STDOUT_FILENO) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn redirect_stdout(_path: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::other("--output is only supported by export commands on this platform; redirect stdout instead"))
+}
+
+fn write_bundle(objects: &[&serde_json::Value], output: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let bundle = serde_json::json!({
+        "type": "bundle",
+        "objects": objects,
+    });
+    write_output(&serde_json::to_string_pretty(&bundle)?, output)
+}
+
+// Past this many group columns a matrix stops being useful in a spreadsheet
+const DEFAULT_MATRIX_COLUMNS: usize = 50;
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        csv_quoted(value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+const TECHNIQUE_CSV_HEADER: &str = "mitre_id,name,tactics,platforms,data_sources,is_subtechnique,deprecated,description";
+
+// Every technique, revoked and deprecated ones included, by MITRE ID
+fn exported_techniques(data: &AttackData) -> Vec<&AttackObject> {
+    let mut techniques: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .collect();
+    sort_objects(&mut techniques, SortOrder::Id);
     techniques
 }
 
-fn get_related_groups<'a>(technique_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
-    let mut related_group_ids = Vec::new();
+// One row per attack-pattern; list columns are semicolon-joined and descriptions always quoted
+fn build_technique_csv(data: &AttackData) -> String {
+    let mut csv = format!("{}\n", TECHNIQUE_CSV_HEADER);
+    for technique in exported_techniques(data) {
+        let list = |values: &Option<Vec<String>>| csv_field(&values.as_deref().unwrap_or_default().join(";"));
+        let row = [
+            csv_field(&get_mitre_id(technique).unwrap_or_default()),
+            csv_field(technique.name.as_deref().unwrap_or_default()),
+            csv_field(&technique_tactics(technique).join(";")),
+            list(&technique.platforms),
+            list(&technique.data_sources),
+            technique.is_subtechnique.unwrap_or(false).to_string(),
+            technique.is_deprecated().to_string(),
+            csv_quoted(technique.description.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+// Fill colors for technique nodes, indexed by the kill-chain position of their earliest tactic
+const DOT_TACTIC_COLORS: [&str; 14] = [
+    "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69",
+    "#fccde5", "#d9d9d9", "#bc80bd", "#ccebc5", "#ffed6f", "#a6cee3", "#e31a1c",
+];
+const DOT_GROUP_COLOR: &str = "#f0f0f0";
+const DOT_UNKNOWN_TACTIC_COLOR: &str = "#ffffff";
+
+fn dot_quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn dot_label(obj: &AttackObject) -> String {
+    dot_quoted(&plain_label(obj))
+}
+
+// Group boxes with an edge to every technique they use; techniques appear once however many groups share them
+fn build_dot_graph(groups: &[&AttackObject], data: &AttackData) -> String {
+    let mut dot = String::from("digraph attack {\n    rankdir=LR;\n    node [style=filled, fontname=\"Helvetica\"];\n\n");
+    let mut edges = String::new();
+    let mut techniques: Vec<&AttackObject> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
     
-    // Find all relationships where groups use this technique
-    for obj in &data.objects {
-        if obj.obj_type == "relationship" {
-            if let (Some(source_ref), Some(target_ref), Some(relationship_type)) = 
-                (&obj.source_ref, &obj.target_ref, &obj.relationship_type) {
-                if target_ref == technique_id && relationship_type == "uses" {
-                    related_group_ids.push(source_ref.as_str());
-                }
+    for group in groups {
+        dot.push_str(&format!("    {} [label={}, shape=box, fillcolor=\"{}\"];\n", dot_quoted(&group.id), dot_label(group), DOT_GROUP_COLOR));
+        let mut used = get_related_techniques(&group.id, data);
+        sort_objects(&mut used, SortOrder::Id);
+        for technique in used {
+            edges.push_str(&format!("    {} -> {};\n", dot_quoted(&group.id), dot_quoted(&technique.id)));
+            if seen.insert(&technique.id) {
+                techniques.push(technique);
             }
         }
     }
     
-    // Get the actual group objects
-    let mut groups = Vec::new();
-    for obj in &data.objects {
-        if obj.obj_type == "intrusion-set" && related_group_ids.contains(&obj.id.as_str()) {
-            groups.push(obj);
-        }
+    sort_objects(&mut techniques, SortOrder::Id);
+    dot.push('\n');
+    for technique in techniques {
+        let tactics = technique_tactics(technique);
+        let color = tactics.iter()
+            .map(|tactic| kill_chain_position(tactic))
+            .min()
+            .and_then(|position| DOT_TACTIC_COLORS.get(position))
+            .unwrap_or(&DOT_UNKNOWN_TACTIC_COLOR);
+        dot.push_str(&format!(
+            "    {} [label={}, shape=ellipse, fillcolor=\"{}\", tooltip={}];\n",
+            dot_quoted(&technique.id), dot_label(technique), color, dot_quoted(&tactics.join(", "))
+        ));
     }
     
-    groups
+    dot.push('\n');
+    dot.push_str(&edges);
+    dot.push_str("}\n");
+    dot
 }
 
-fn print_separator() {
-    println!("{}", "─".repeat(80).bright_black());
+// Mermaid node IDs may only contain letters, digits and underscores
+fn mermaid_id(value: &str) -> String {
+    value.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
 }
 
-fn print_technique_info(obj: &AttackObject, data: &AttackData) {
-    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
-    
-    if let Some(mitre_id) = get_mitre_id(obj) {
-        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
-    }
-    
-    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
-    
-    if let Some(desc) = &obj.description {
-        println!("\n{}", "Description:".bright_white().bold());
-        println!("{}", desc);
-    }
+fn mermaid_label(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
+
+// `graph LR` with the group linked to one subgraph per tactic holding its techniques. Node IDs are the
+// MITRE IDs; a technique under several tactics gets the tactic appended after its first appearance.
+fn build_mermaid_graph(group: &AttackObject, techniques: &[&AttackObject], via: &ViaSoftware) -> String {
+    let group_id = mermaid_id(&get_mitre_id(group).unwrap_or_else(|| group.id.clone()));
+    let mut graph = String::from("graph LR\n");
+    graph.push_str(&format!("    {}[{}]\n", group_id, mermaid_label(&plain_label(group))));
     
-    if let Some(tactics) = &obj.kill_chain_phases {
-        println!("\n{}", "Tactics:".bright_white().bold());
-        for tactic in tactics {
-            if tactic.kill_chain_name == "mitre-attack" {
-                println!("  • {}", tactic.phase_name.bright_magenta());
+    let mut edges = String::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for (tactic, mut tactic_techniques) in group_techniques_by_tactic(techniques) {
+        let tactic_id = mermaid_id(&tactic.to_lowercase());
+        graph.push_str(&format!("    subgraph {}[{}]\n", tactic_id, mermaid_label(&tactic)));
+        sort_objects(&mut tactic_techniques, SortOrder::Id);
+        for technique in tactic_techniques {
+            let mut node_id = mermaid_id(&get_mitre_id(technique).unwrap_or_else(|| technique.id.clone()));
+            if !seen.insert(node_id.clone()) {
+                node_id = format!("{}_{}", node_id, tactic_id);
             }
+            let label = format!("{}{}", plain_label(technique), via_suffix(via, technique));
+            graph.push_str(&format!("        {}[{}]\n", node_id, mermaid_label(&label)));
         }
+        graph.push_str("    end\n");
+        edges.push_str(&format!("    {} --> {}\n", group_id, tactic_id));
     }
+    graph.push_str(&edges);
+    graph
+}
+
+// `[ID] Name`, as the graph exports label their nodes
+fn plain_label(obj: &AttackObject) -> String {
+    let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+    format!("[{}] {}", mitre_id, obj.name.as_deref().unwrap_or("Unknown"))
+}
+
+// Navigator layer format version the generated layers conform to
+const NAVIGATOR_LAYER_VERSION: &str = "4.5";
+const NAVIGATOR_VERSION: &str = "4.9.1";
+
+fn build_navigator_layer(group: &AttackObject, data: &AttackData, domain: Domain) -> serde_json::Value {
+    let group_name = group.name.as_deref().unwrap_or("Unknown");
+    let group_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
     
-    if let Some(platforms) = &obj.platforms {
-        println!("\n{}", "Platforms:".bright_white().bold());
-        for platform in platforms {
-            println!("  • {}", platform.bright_blue());
-        }
-    }
+    let mut technique_ids: Vec<String> = get_related_techniques(&group.id, data)
+        .into_iter()
+        .filter_map(get_mitre_id)
+        .collect();
+    technique_ids.sort();
+    technique_ids.dedup();
     
-    if let Some(perms) = &obj.permissions_required {
-        println!("\n{}", "Permissions Required:".bright_white().bold());
-        for perm in perms {
-            println!("  • {}", perm.bright_red());
+    let techniques: Vec<serde_json::Value> = technique_ids.iter()
+        .map(|technique_id| serde_json::json!({
+            "techniqueID": technique_id,
+            "score": 1,
+            "enabled": true,
+        }))
+        .collect();
+    
+    serde_json::json!({
+        "name": format!("{} ({})", group_name, group_id),
+        "versions": {
+            "layer": NAVIGATOR_LAYER_VERSION,
+            "navigator": NAVIGATOR_VERSION,
+        },
+        "domain": domain.stix_name(),
+        "description": format!("Techniques used by {} ({})", group_name, group_id),
+        "techniques": techniques,
+        "gradient": {
+            "colors": ["#ffffff", "#ff6666"],
+            "minValue": 0,
+            "maxValue": 1,
+        },
+        "hideDisabled": false,
+    })
+}
+
+// One row per technique, one 1/0 column per group, pivoted from the `uses` relationships
+fn build_group_technique_matrix(groups: &[&AttackObject], data: &AttackData) -> String {
+    let mut used: HashSet<(&str, &str)> = HashSet::new();
+    for obj in &data.objects {
+        if obj.obj_type == "relationship"
+            && let (Some(source_ref), Some(target_ref), Some(relationship_type)) =
+                (&obj.source_ref, &obj.target_ref, &obj.relationship_type)
+            && relationship_type == "uses" {
+            used.insert((source_ref.as_str(), target_ref.as_str()));
         }
     }
     
-    if let Some(detection) = &obj.detection {
-        println!("\n{}", "Detection:".bright_white().bold());
-        println!("{}", detection);
-    }
+    let mut techniques: Vec<(String, &AttackObject)> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern" && !obj.is_sunset())
+        .filter_map(|obj| get_mitre_id(obj).map(|mitre_id| (mitre_id, obj)))
+        .collect();
+    techniques.sort_by(|a, b| compare_mitre_ids(&a.0, &b.0));
     
-    if let Some(data_sources) = &obj.data_sources {
-        println!("\n{}", "Data Sources:".bright_white().bold());
-        for source in data_sources {
-            println!("  • {}", source.bright_cyan());
-        }
+    let mut csv = String::from("technique_id,technique_name");
+    for group in groups {
+        csv.push(',');
+        csv.push_str(&csv_field(group.name.as_deref().unwrap_or(&group.id)));
     }
+    csv.push('\n');
     
-    // Show which groups use this technique
-    let related_groups = get_related_groups(&obj.id, data);
-    if !related_groups.is_empty() {
-        println!("\n{}", "Used by Groups:".bright_white().bold());
-        let mut sorted_groups = related_groups;
-        sorted_groups.sort_by(|a, b| {
-            a.name.as_ref().unwrap_or(&"".to_string())
-                .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
-        });
-        
-        for group in sorted_groups {
-            if let Some(group_name) = &group.name {
-                let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
-                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), group_name.bright_white());
-            }
+    for (mitre_id, technique) in techniques {
+        csv.push_str(&csv_field(&mitre_id));
+        csv.push(',');
+        csv.push_str(&csv_field(technique.name.as_deref().unwrap_or("")));
+        for group in groups {
+            let cell = if used.contains(&(group.id.as_str(), technique.id.as_str())) { ",1" } else { ",0" };
+            csv.push_str(cell);
         }
+        csv.push('\n');
     }
-    
-    if let Some(refs) = &obj.external_references {
-        println!("\n{}", "References:".bright_white().bold());
-        for ref_obj in refs {
-            if let Some(url) = &ref_obj.url {
-                println!("  • {} - {}", ref_obj.source_name.bright_green(), url.bright_blue().underline());
+    csv
+}
+
+// Swaps revoked objects for their replacements, telling the user about each swap.
+// The notice goes to stderr in JSON mode so stdout stays parseable.
+fn follow_revoked<'a>(found: Vec<&'a AttackObject>, data: &'a AttackData, machine_readable: bool) -> Vec<&'a AttackObject> {
+    let resolved = found.into_iter()
+        .map(|obj| {
+            let replacement = resolve_revoked(obj, data);
+            if !std::ptr::eq(obj, replacement) {
+                let old_id = get_mitre_id(obj).unwrap_or_else(|| obj.id.clone());
+                let new_id = get_mitre_id(replacement).unwrap_or_else(|| replacement.id.clone());
+                let notice = format!("{} was revoked; showing {} instead", old_id, new_id);
+                if machine_readable {
+                    eprintln!("{}", notice.yellow());
+                } else {
+                    println!("{}", notice.yellow());
+                }
             }
+            replacement
+        })
+        .collect();
+    dedupe_by_id(resolved)
+}
+
+// Newline-separated IDs from stdin, for `tid -`; blank lines are skipped
+fn read_stdin_ids() -> std::io::Result<Vec<String>> {
+    let mut ids = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            ids.push(line.trim().to_string());
         }
     }
+    Ok(ids)
 }
 
-fn print_group_info(obj: &AttackObject, data: &AttackData) {
-    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
-    
-    if let Some(mitre_id) = get_mitre_id(obj) {
-        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+// One compact JSON object per line (NDJSON), so pipelines can process items as they stream in
+fn json_lines<T: Serialize>(items: &[T]) -> Result<String, serde_json::Error> {
+    let mut lines = String::new();
+    for item in items {
+        lines.push_str(&serde_json::to_string(item)?);
+        lines.push('\n');
     }
-    
-    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
-    
-    if let Some(aliases) = &obj.aliases {
-        println!("\n{}", "Aliases:".bright_white().bold());
-        for alias in aliases {
-            println!("  • {}", alias.bright_magenta());
-        }
+    Ok(lines)
+}
+
+// Ends a command with a failing exit status but no error message, e.g. a search that found nothing
+#[derive(Debug)]
+struct QuietFailure;
+
+impl std::fmt::Display for QuietFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "nothing found")
     }
-    
-    if let Some(desc) = &obj.description {
-        println!("\n{}", "Description:".bright_white().bold());
-        println!("{}", desc);
+}
+
+impl std::error::Error for QuietFailure {}
+
+// Prints matches as JSON: a lone match as an object, anything else as an array.
+// Exits non-zero when nothing matched so scripts can detect the failure.
+fn print_json_matches<T: Serialize>(matches: &[T]) -> Result<(), Box<dyn std::error::Error>> {
+    match matches {
+        [single] => println!("{}", serde_json::to_string_pretty(single)?),
+        _ => println!("{}", serde_json::to_string_pretty(matches)?),
     }
-    
-    // Find related techniques through relationships
-    let related_techniques = get_related_techniques(&obj.id, data);
-    if !related_techniques.is_empty() {
-        println!("\n{}", "Used Techniques:".bright_white().bold());
-        
-        // Group techniques by tactic
-        let mut tactics_map: HashMap<String, Vec<&AttackObject>> = HashMap::new();
-        
-        for technique in &related_techniques {
-            if let Some(phases) = &technique.kill_chain_phases {
-                for phase in phases {
-                    if phase.kill_chain_name == "mitre-attack" {
-                        let tactic_name = phase.phase_name.replace("-", " ");
-                        let tactic_name = tactic_name.split_whitespace()
-                            .map(|s| s.chars().next().unwrap().to_uppercase().collect::<String>() + &s[1..])
-                            .collect::<Vec<_>>()
-                            .join(" ");
-                        
-                        tactics_map.entry(tactic_name)
-                            .or_insert_with(Vec::new)
-                            .push(technique);
-                    }
-                }
-            }
-        }
-        
-        // Sort tactics alphabetically
-        let mut sorted_tactics: Vec<_> = tactics_map.iter().collect();
-        sorted_tactics.sort_by(|a, b| a.0.cmp(b.0));
+    if matches.is_empty() {
+        return Err(QuietFailure.into());
+    }
+    Ok(())
+}
+
+// The YAML counterpart of print_json_matches, with the same shape and exit status
+fn print_yaml_matches<T: Serialize>(matches: &[T]) -> Result<(), Box<dyn std::error::Error>> {
+    match matches {
+        [single] => print!("{}", to_yaml(&serde_json::to_value(single)?)),
+        _ => print!("{}", to_yaml(&serde_json::to_value(matches)?)),
+    }
+    if matches.is_empty() {
+        return Err(QuietFailure.into());
+    }
+    Ok(())
+}
+
+// Prints matches in a structured format; false means the caller should print formatted text
+fn print_structured_matches<T: Serialize>(matches: &[T], format: ObjectFormat) -> Result<bool, Box<dyn std::error::Error>> {
+    match format {
+        ObjectFormat::Text => return Ok(false),
+        ObjectFormat::Json => print_json_matches(matches)?,
+        ObjectFormat::Yaml => print_yaml_matches(matches)?,
+    }
+    Ok(true)
+}
+
+// Width used when nothing more specific is known about the terminal
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+// Narrowest a truncatable column may get before we stop shrinking it
+const MIN_TRUNCATED_COLUMN_WIDTH: usize = 8;
+
+// Read once, before paging points stdout at a pipe
+static TERMINAL_SIZE: LazyLock<Option<TerminalSize>> = LazyLock::new(terminal_size);
+
+#[derive(Clone, Copy)]
+struct TerminalSize {
+    rows: usize,
+    columns: usize,
+}
+
+// Width of the terminal stdout is attached to, then $COLUMNS, then DEFAULT_TERMINAL_WIDTH
+fn get_terminal_width() -> usize {
+    TERMINAL_SIZE.map(|size| size.columns)
+        .or_else(|| std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()))
+        .filter(|&columns| columns > 0)
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+#[cfg(unix)]
+fn terminal_size() -> Option<TerminalSize> {
+    let mut size = libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+    // SAFETY: TIOCGWINSZ only writes a winsize into the pointer it is given
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    (result == 0).then_some(TerminalSize { rows: usize::from(size.ws_row), columns: usize::from(size.ws_col) })
+}
+
+#[cfg(not(unix))]
+fn terminal_size() -> Option<TerminalSize> {
+    None
+}
+
+// Pages output that does not fit on the terminal: stdout is pointed at a pipe while the command runs
+// and buffered in memory, then printed directly or piped through $PAGER (default `less -R`, which
+// keeps colors)
+#[cfg(unix)]
+struct Pager {
+    saved_stdout: i32,
+    // Drains the pipe as the command writes, so output larger than the pipe buffer cannot block it
+    reader: Option<std::thread::JoinHandle<Vec<u8>>>,
+}
+
+#[cfg(unix)]
+impl Pager {
+    fn start() -> Option<Pager> {
+        use std::io::Read;
+        use std::os::fd::AsRawFd;
         
-        for (tactic, techniques) in sorted_tactics {
-            println!("\n  {}", format!("{}:", tactic).bright_magenta().bold());
-            let mut sorted_techniques = techniques.clone();
-            sorted_techniques.sort_by(|a, b| {
-                a.name.as_ref().unwrap_or(&"".to_string())
-                    .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
-            });
-            
-            for technique in sorted_techniques {
-                if let Some(tech_name) = &technique.name {
-                    let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
-                    println!("    {} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
-                }
-            }
+        let (mut reader, writer) = std::io::pipe().ok()?;
+        // SAFETY: dup only duplicates a descriptor this process has open
+        let saved_stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if saved_stdout == -1 {
+            return None;
+        }
+        // SAFETY: both descriptors are open; dup2 only makes STDOUT_FILENO refer to the pipe
+        if unsafe { libc::dup2(writer.as_raw_fd(), libc::STDOUT_FILENO) } == -1 {
+            // SAFETY: saved_stdout was just opened by dup and is not used elsewhere
+            unsafe { libc::close(saved_stdout) };
+            return None;
         }
+        // STDOUT_FILENO is now the only write end, so restoring it is what ends the reader
+        drop(writer);
         
-        println!("\n{}", format!("Total Techniques: {}", related_techniques.len()).bright_cyan());
+        let reader = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = reader.read_to_end(&mut output);
+            output
+        });
+        Some(Pager { saved_stdout, reader: Some(reader) })
     }
     
-    if let Some(refs) = &obj.external_references {
-        println!("\n{}", "References:".bright_white().bold());
-        for ref_obj in refs {
-            if let Some(url) = &ref_obj.url {
-                println!("  • {} - {}", ref_obj.source_name.bright_green(), url.bright_blue().underline());
-            }
+    // Points stdout back at the terminal and returns what was captured; empty once already restored
+    fn restore(&mut self) -> Vec<u8> {
+        let Some(reader) = self.reader.take() else {
+            return Vec::new();
+        };
+        let _ = std::io::stdout().flush();
+        // SAFETY: saved_stdout is the original stdout, duplicated in start and closed only here
+        unsafe {
+            libc::dup2(self.saved_stdout, libc::STDOUT_FILENO);
+            libc::close(self.saved_stdout);
+        }
+        reader.join().unwrap_or_default()
+    }
+    
+    fn finish(mut self) {
+        let output = self.restore();
+        let lines = output.iter().filter(|&&byte| byte == b'\n').count();
+        // A terminal reporting 0 rows has an unknown height
+        let fits = TERMINAL_SIZE.is_none_or(|size| size.rows == 0 || lines < size.rows);
+        if fits || !run_pager(&output) {
+            let _ = std::io::stdout().write_all(&output);
         }
     }
 }
 
-fn print_tactic_info(obj: &AttackObject) {
-    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
-    
-    if let Some(shortname) = &obj.shortname {
-        println!("{}", format!("Short Name: {}", shortname).bright_green());
+// Only still capturing when the command panicked: put stdout back and show what it printed
+#[cfg(unix)]
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let output = self.restore();
+        let _ = std::io::stdout().write_all(&output);
+    }
+}
+
+#[cfg(not(unix))]
+struct Pager;
+
+#[cfg(not(unix))]
+impl Pager {
+    fn start() -> Option<Pager> {
+        None
     }
     
-    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
-    
-    if let Some(desc) = &obj.description {
-        println!("\n{}", "Description:".bright_white().bold());
-        println!("{}", desc);
+    fn finish(self) {}
+}
+
+// False when the pager could not be started, so the caller prints the output itself
+#[cfg(unix)]
+fn run_pager(output: &[u8]) -> bool {
+    let pager = std::env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty()).unwrap_or_else(|| "less -R".to_string());
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+    let Ok(mut child) = std::process::Command::new(program).args(words).stdin(std::process::Stdio::piped()).spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Fails with a broken pipe when the user quits before reaching the end, which is fine
+        let _ = stdin.write_all(output);
+    }
+    let _ = child.wait();
+    true
+}
+
+// Columns a string occupies on screen, not counting ANSI color sequences
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+// Word-wraps text to `width` columns. Existing line and paragraph breaks are kept, and words
+// longer than the width stay whole on their own line. A width of 0 disables wrapping.
+fn wrap_text(text: &str, width: usize) -> String {
+    if width == 0 {
+        return text.to_string();
     }
     
-    if let Some(refs) = &obj.external_references {
-        println!("\n{}", "References:".bright_white().bold());
-        for ref_obj in refs {
-            if let Some(url) = &ref_obj.url {
-                println!("  • {} - {}", ref_obj.source_name.bright_green(), url.bright_blue().underline());
+    let mut wrapped = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            wrapped.push('\n');
+        }
+        let mut column = 0;
+        for word in line.split_whitespace() {
+            let word_width = visible_width(word);
+            if column > 0 && column + 1 + word_width > width {
+                wrapped.push('\n');
+                column = 0;
+            } else if column > 0 {
+                wrapped.push(' ');
+                column += 1;
             }
+            wrapped.push_str(word);
+            column += word_width;
         }
     }
+    wrapped
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    let data = load_attack_data()?;
+// Characters of text shown either side of a search or search-detection match
+const SNIPPET_CONTEXT: usize = 40;
 
-    match &cli.command {
-        Commands::AptList => {
-            println!("{}", "APT Groups (Advanced Persistent Threat Groups):".bright_cyan().bold());
-            print_separator();
-            
-            let mut groups: Vec<&AttackObject> = data.objects
-                .iter()
+// Marks the part of a text a query matched: bold and underlined, or >>..<< when colors are off
+fn emphasize(matched: ColoredString) -> String {
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        matched.bold().underline().to_string()
+    } else {
+        format!(">>{}<<", matched)
+    }
+}
+
+// `text` in `style`, with its first case-insensitive match of `query` emphasized so users see why it matched
+fn highlight_match(text: &str, query: Option<&str>, style: impl Fn(&str) -> ColoredString) -> String {
+    let Some(snippet) = query.and_then(|query| find_snippet(text, query, usize::MAX)) else {
+        return style(text).to_string();
+    };
+    format!("{}{}{}", style(snippet.before), emphasize(style(snippet.matched)), style(snippet.after))
+}
+
+// One line of context around a match, with the match emphasized
+fn print_snippet(snippet: &Snippet) {
+    // Descriptions and detection text span paragraphs; keep each snippet on one line
+    let flatten = |text: &str| text.replace(['\n', '\r', '\t'], " ");
+    println!(
+        "  {}{}{}{}{}",
+        if snippet.clipped_start { "…" } else { "" },
+        flatten(snippet.before).bright_black(),
+        emphasize(snippet.matched.bright_yellow()),
+        flatten(snippet.after).bright_black(),
+        if snippet.clipped_end { "…" } else { "" },
+    );
+}
+
+// Longest description excerpt --short prints before cutting it off
+const SHORT_SUMMARY_LENGTH: usize = 120;
+
+// `[ID] Name — first sentence (platforms)` on one line, for --short
+fn print_short_summary(obj: &AttackObject, highlight: Option<&str>) {
+    let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+    let name = highlight_match(obj.name.as_deref().unwrap_or("Unknown"), highlight, |text| text.bright_white());
+    let mut line = format!("{} {}", format!("[{}]", mitre_id).bright_green(), name);
+    if let Some(description) = &obj.description {
+        line.push_str(&format!(" — {}", first_sentence(description, SHORT_SUMMARY_LENGTH)));
+    }
+    if let Some(platforms) = obj.platforms.as_ref().filter(|platforms| !platforms.is_empty()) {
+        line.push_str(&format!(" {}", format!("({})", platforms.join(", ")).bright_black()));
+    }
+    println!("{}", line);
+}
+
+fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+// Prints rows as aligned columns. When `max_width` is non-zero, the widest columns flagged
+// in `truncatable` are shrunk one character at a time until the table fits.
+fn print_table(headers: &[&str], rows: &[Vec<String>], truncatable: &[bool], max_width: usize) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    
+    if max_width > 0 {
+        let separators = 2 * widths.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + separators > max_width {
+            let widest = widths.iter()
+                .enumerate()
+                .filter(|(i, width)| truncatable[*i] && **width > MIN_TRUNCATED_COLUMN_WIDTH)
+                .max_by_key(|(_, width)| **width)
+                .map(|(i, _)| i);
+            match widest {
+                Some(i) => widths[i] -= 1,
+                None => break,
+            }
+        }
+    }
+    
+    let format_row = |cells: &mut dyn Iterator<Item = &str>| {
+        cells.zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", truncate_with_ellipsis(cell, *width), width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    
+    println!("{}", format_row(&mut headers.iter().copied()).bright_white().bold());
+    println!("{}", widths.iter().map(|width| "─".repeat(*width)).collect::<Vec<_>>().join("  ").bright_black());
+    for row in rows {
+        println!("{}", format_row(&mut row.iter().map(String::as_str)));
+    }
+}
+
+const CITATION_PREFIX: &str = "(Citation: ";
+
+// Numbers citation markers in an object's text against its external references.
+// When disabled, text passes through untouched.
+struct CitationResolver<'a> {
+    enabled: bool,
+    hyperlinks: bool,
+    references: &'a [ExternalReference],
+    cited: Vec<&'a ExternalReference>,
+}
+
+impl<'a> CitationResolver<'a> {
+    fn new(obj: &'a AttackObject, options: &DisplayOptions) -> Self {
+        CitationResolver {
+            enabled: options.resolve_citations,
+            hyperlinks: options.hyperlinks,
+            references: obj.external_references.as_deref().unwrap_or(&[]),
+            cited: Vec::new(),
+        }
+    }
+    
+    fn resolve(&mut self, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        
+        let mut resolved = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(CITATION_PREFIX) {
+            let Some(len) = rest[start..].find(')') else { break };
+            let marker = &rest[start..start + len + 1];
+            let source_name = &marker[CITATION_PREFIX.len()..marker.len() - 1];
+            resolved.push_str(&rest[..start]);
+            
+            match self.references.iter().find(|r| r.source_name == source_name && r.url.is_some()) {
+                Some(reference) => {
+                    let number = match self.cited.iter().position(|c| std::ptr::eq(*c, reference)) {
+                        Some(i) => i + 1,
+                        None => {
+                            self.cited.push(reference);
+                            self.cited.len()
+                        }
+                    };
+                    resolved.push_str(&format!("[{}]", number).bright_blue().to_string());
+                }
+                None => resolved.push_str(&marker.bright_black().to_string()),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        resolved.push_str(rest);
+        resolved
+    }
+    
+    fn print_footnotes(&self) {
+        if self.cited.is_empty() {
+            return;
+        }
+        println!("\n{}", "Cited References:".bright_white().bold());
+        for (i, reference) in self.cited.iter().enumerate() {
+            let url = reference.url.as_deref().unwrap_or_default();
+            println!("  {} {}", format!("[{}]", i + 1).bright_blue(), reference_line(&reference.source_name, url, self.hyperlinks));
+        }
+    }
+}
+
+// How many "Did you mean" suggestions to offer after a failed lookup
+const SUGGESTION_LIMIT: usize = 5;
+
+fn print_suggestions(query: &str, kind: ObjectKind, data: &AttackData) {
+    let suggestions = suggest_similar(query, kind, data, SUGGESTION_LIMIT);
+    if suggestions.is_empty() {
+        return;
+    }
+    
+    println!("\n{}", "Did you mean:".bright_white().bold());
+    for (obj, matched_name) in suggestions {
+        let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+        let obj_name = obj.name.as_deref().unwrap_or("Unknown");
+        if matched_name == obj_name {
+            println!("  {} {}", format!("[{}]", mitre_id).bright_green(), obj_name.bright_white());
+        } else {
+            println!("  {} {} {}", format!("[{}]", mitre_id).bright_green(), obj_name.bright_white(), format!("(alias: {})", matched_name).bright_black());
+        }
+    }
+}
+
+// Keeps the first `limit` items (0 keeps them all) and returns how many were dropped
+fn apply_limit<T>(items: &mut Vec<T>, limit: usize) -> usize {
+    let hidden = if limit == 0 { 0 } else { items.len().saturating_sub(limit) };
+    items.truncate(items.len() - hidden);
+    hidden
+}
+
+fn print_limit_notice(hidden: usize) {
+    if hidden > 0 {
+        println!("{}", format!("… and {} more (use --limit 0 for all)", hidden).bright_black());
+    }
+}
+
+// Tags a name in --deprecated-only listings: " (deprecated)", or " (revoked, replaced by T1055)"
+fn sunset_note(obj: &AttackObject, data: &AttackData) -> String {
+    if obj.revoked != Some(true) {
+        return " (deprecated)".to_string();
+    }
+    match replacement_of(obj, data) {
+        Some(replacement) => format!(" (revoked, replaced by {})", get_mitre_id(replacement).unwrap_or_else(|| replacement.id.clone())),
+        None => " (revoked)".to_string(),
+    }
+}
+
+// Describes an active platform filter for "not found" messages
+fn platform_suffix(platforms: &[String], mode: PlatformMatch) -> String {
+    match mode {
+        _ if platforms.is_empty() => String::new(),
+        PlatformMatch::Any => format!(" on platform {}", platforms.join(" or ")),
+        PlatformMatch::All => format!(" on platforms {}", platforms.join(" and ")),
+    }
+}
+
+// Every enterprise tactic gets its own color, plus a short ASCII tag that stands in for it
+// when colors are off. Neighbouring tactics in the kill chain never share a color.
+static TACTIC_STYLES: LazyLock<HashMap<&'static str, (Color, &'static str)>> = LazyLock::new(|| HashMap::from([
+    ("reconnaissance", (Color::Blue, "RECON")),
+    ("resource-development", (Color::Cyan, "RESDEV")),
+    ("initial-access", (Color::BrightGreen, "INIT")),
+    ("execution", (Color::BrightRed, "EXEC")),
+    ("persistence", (Color::Yellow, "PERSIST")),
+    ("privilege-escalation", (Color::BrightMagenta, "PRIVESC")),
+    ("defense-evasion", (Color::Green, "EVADE")),
+    ("credential-access", (Color::BrightYellow, "CRED")),
+    ("discovery", (Color::BrightBlue, "DISC")),
+    ("lateral-movement", (Color::Magenta, "LATERAL")),
+    ("collection", (Color::BrightCyan, "COLLECT")),
+    ("command-and-control", (Color::Red, "C2")),
+    ("exfiltration", (Color::TrueColor { r: 255, g: 135, b: 0 }, "EXFIL")),
+    ("impact", (Color::TrueColor { r: 215, g: 0, b: 95 }, "IMPACT")),
+]));
+
+// Colors from TACTIC_STYLES that tactics of other domains are spread over
+const FALLBACK_TACTIC_COLORS: [Color; 6] = [
+    Color::BrightMagenta, Color::BrightCyan, Color::BrightYellow, Color::BrightBlue, Color::Magenta, Color::Cyan,
+];
+
+// Accepts a shortname ("privilege-escalation") or its title-cased form ("Privilege Escalation")
+fn tactic_style(tactic: &str) -> (Color, String) {
+    let shortname = tactic.trim().to_lowercase().replace(' ', "-");
+    if let Some((color, tag)) = TACTIC_STYLES.get(shortname.as_str()) {
+        return (*color, tag.to_string());
+    }
+    // Mobile and ICS tactics: a color picked from the name, so it is the same on every run
+    let hash = shortname.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize));
+    let tag = shortname.split('-').filter_map(|word| word.chars().next()).collect::<String>().to_uppercase();
+    (FALLBACK_TACTIC_COLORS[hash % FALLBACK_TACTIC_COLORS.len()], tag)
+}
+
+// The tactic whose name or shortname is exactly `name`, aliases included
+fn find_tactic<'a>(name: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    let wanted = normalize_tactic_name(name);
+    get_tactics_in_kill_chain_order(data).into_iter().find(|obj| {
+        obj.shortname.as_deref().is_some_and(|shortname| normalize_tactic_name(shortname) == wanted)
+            || obj.name.as_deref().is_some_and(|name| normalize_tactic_name(name) == wanted)
+    })
+}
+
+// `text` in the tactic's color, or prefixed with its ASCII tag when colors are disabled
+fn tactic_label(tactic: &str, text: &str) -> ColoredString {
+    let (color, tag) = tactic_style(tactic);
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        text.color(color)
+    } else {
+        format!("[{}] {}", tag, text).normal()
+    }
+}
+
+// Which software each transitively used technique comes through, keyed by technique STIX id
+type ViaSoftware<'a> = HashMap<&'a str, String>;
+
+// A group's techniques for display; with --transitive, also those of its software
+fn group_technique_footprint<'a>(group: &AttackObject, data: &'a AttackData, options: &DisplayOptions) -> (Vec<&'a AttackObject>, ViaSoftware<'a>) {
+    if !options.transitive {
+        return (get_related_techniques(&group.id, data), HashMap::new());
+    }
+    
+    let mut via = HashMap::new();
+    let techniques = get_all_group_techniques(&group.id, data)
+        .into_iter()
+        .map(|entry| {
+            if !entry.via.is_empty() {
+                let names: Vec<&str> = entry.via.iter().map(|tool| tool.name.as_deref().unwrap_or("Unknown")).collect();
+                via.insert(entry.technique.id.as_str(), names.join(", "));
+            }
+            entry.technique
+        })
+        .collect();
+    (techniques, via)
+}
+
+fn via_suffix(via: &ViaSoftware, technique: &AttackObject) -> String {
+    via.get(technique.id.as_str()).map(|names| format!(" (via {})", names)).unwrap_or_default()
+}
+
+// Prints a "Used Techniques" section bucketed by tactic, honoring the platform filter
+fn print_used_techniques(techniques: &[&AttackObject], via: &ViaSoftware, data: &AttackData, options: &DisplayOptions) {
+    if techniques.is_empty() {
+        return;
+    }
+    println!("\n{}", "Used Techniques:".bright_white().bold());
+    
+    let filtered: Vec<&AttackObject> = techniques.iter()
+        .copied()
+        .filter(|technique| matches_platforms(technique, &options.platforms, options.platform_match))
+        .collect();
+    if filtered.is_empty() {
+        println!("  {}", format!("No techniques match the platform filter ({})", options.platforms.join(", ")).yellow());
+        return;
+    }
+    
+    for (number, (tactic, mut techniques)) in group_techniques_in_order(&filtered, options.tactic_order, data).into_iter().enumerate() {
+        let heading = match options.tactic_order {
+            TacticOrder::Killchain => format!("{}. {}:", number + 1, tactic),
+            TacticOrder::Alpha => format!("{}:", tactic),
+        };
+        println!("\n  {}", tactic_label(&tactic, &heading).bold());
+        sort_objects(&mut techniques, options.sort);
+        let hidden = apply_limit(&mut techniques, options.technique_limit);
+        
+        for technique in techniques {
+            if let Some(tech_name) = &technique.name {
+                let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                // --highlight-platform: the name in red, tagged so the mark survives without colors
+                let highlighted = options.highlight_platform.as_ref()
+                    .filter(|platform| matches_platforms(technique, std::slice::from_ref(platform), PlatformMatch::Any));
+                let (name, tag) = match highlighted {
+                    Some(platform) => (tech_name.bright_red().bold(), format!(" [{}]", platform).bright_red()),
+                    None => (tech_name.bright_white(), "".normal()),
+                };
+                println!("    {} {}{}{}", format!("[{}]", mitre_id).bright_green(), name, tag, via_suffix(via, technique).bright_black());
+            }
+        }
+        if hidden > 0 {
+            println!("    {}", format!("… +{} more in this tactic", hidden).bright_black());
+        }
+    }
+    
+    let (label, total, breakdown) = technique_totals(&filtered, via, options.technique_count);
+    println!("\n{}", format!("{}: {}", label, total).bright_cyan());
+    println!("{}", breakdown.bright_black());
+}
+
+// The label and value of a profile's total, which counts what --count asks for, and the
+// parent/sub-technique breakdown shown beneath it
+fn technique_totals(techniques: &[&AttackObject], via: &ViaSoftware, count: TechniqueCount) -> (&'static str, String, String) {
+    let counted: Vec<&AttackObject> = techniques.iter().copied().filter(|technique| count.includes(technique)).collect();
+    let label = match count {
+        TechniqueCount::All => "Total Techniques",
+        TechniqueCount::Parents => "Total Parent Techniques",
+        TechniqueCount::Subs => "Total Sub-techniques",
+    };
+    let transitive = counted.iter().filter(|technique| via.contains_key(technique.id.as_str())).count();
+    let total = if transitive > 0 {
+        format!("{} ({} via software)", counted.len(), transitive)
+    } else {
+        counted.len().to_string()
+    };
+    let subs = techniques.iter().filter(|technique| TechniqueCount::Subs.includes(technique)).count();
+    (label, total, format!("Parent techniques: {}, Sub-techniques: {}", techniques.len() - subs, subs))
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut table = format!("| {} |\n", headers.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| markdown_escape(cell)).collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+fn render_group_markdown(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) -> String {
+    let mut citations = CitationResolver::new(obj, options);
+    let name = obj.name.as_deref().unwrap_or("Unknown");
+    let mut md = match get_mitre_id(obj) {
+        Some(mitre_id) => format!("# {} ({})\n", name, mitre_id),
+        None => format!("# {}\n", name),
+    };
+    
+    if let Some(aliases) = &obj.aliases {
+        md.push_str("\n**Aliases:**\n\n");
+        for alias in aliases {
+            md.push_str(&format!("- {}\n", alias));
+        }
+    }
+    
+    if let Some(desc) = &obj.description {
+        md.push_str(&format!("\n## Description\n\n{}\n", citations.resolve(desc)));
+    }
+    
+    if options.profile {
+        let software: Vec<Vec<String>> = get_group_software(&obj.id, data).iter()
+            .map(|tool| vec![
+                get_mitre_id(tool).unwrap_or_else(|| "N/A".to_string()),
+                tool.name.clone().unwrap_or_default(),
+                tool.obj_type.clone(),
+            ])
+            .collect();
+        md.push_str("\n## Software Used\n\n");
+        md.push_str(&if software.is_empty() { "None documented.\n".to_string() } else { markdown_table(&["ID", "Name", "Type"], &software) });
+        
+        let campaigns: Vec<Vec<String>> = get_group_campaigns(&obj.id, data).iter()
+            .map(|campaign| vec![
+                get_mitre_id(campaign).unwrap_or_else(|| "N/A".to_string()),
+                campaign.name.clone().unwrap_or_default(),
+                campaign_period(campaign),
+            ])
+            .collect();
+        md.push_str("\n## Associated Campaigns\n\n");
+        md.push_str(&if campaigns.is_empty() { "None documented.\n".to_string() } else { markdown_table(&["ID", "Name", "Active"], &campaigns) });
+    }
+    
+    let (techniques, via) = group_technique_footprint(obj, data, options);
+    let techniques: Vec<&AttackObject> = techniques
+        .into_iter()
+        .filter(|technique| matches_platforms(technique, &options.platforms, options.platform_match))
+        .collect();
+    for (number, (tactic, mut tactic_techniques)) in group_techniques_in_order(&techniques, options.tactic_order, data).into_iter().enumerate() {
+        sort_objects(&mut tactic_techniques, options.sort);
+        let hidden = apply_limit(&mut tactic_techniques, options.technique_limit);
+        let rows: Vec<Vec<String>> = tactic_techniques.iter()
+            .map(|technique| vec![
+                get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string()),
+                format!("{}{}", technique.name.as_deref().unwrap_or_default(), via_suffix(&via, technique)),
+            ])
+            .collect();
+        let heading = match options.tactic_order {
+            TacticOrder::Killchain => format!("{}. {}", number + 1, tactic),
+            TacticOrder::Alpha => tactic,
+        };
+        md.push_str(&format!("\n## {}\n\n{}", heading, markdown_table(&["ID", "Technique"], &rows)));
+        if hidden > 0 {
+            md.push_str(&format!("\n_… +{} more in this tactic_\n", hidden));
+        }
+    }
+    if !techniques.is_empty() {
+        let (label, total, breakdown) = technique_totals(&techniques, &via, options.technique_count);
+        md.push_str(&format!("\n**{}:** {}\n\n{}\n", label, total, breakdown));
+    }
+    
+    if !citations.cited.is_empty() {
+        md.push_str("\n## Cited References\n\n");
+        for (i, reference) in citations.cited.iter().enumerate() {
+            let url = reference.url.as_deref().unwrap_or_default();
+            md.push_str(&format!("{}. [{}]({})\n", i + 1, reference.source_name, url));
+        }
+    }
+    
+    if !options.hide_references
+        && let Some(refs) = &obj.external_references {
+        md.push_str("\n## References\n\n");
+        for ref_obj in refs {
+            if let Some(url) = &ref_obj.url {
+                md.push_str(&format!("- [{}]({})\n", ref_obj.source_name, url));
+            }
+        }
+    }
+    
+    md
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_REPORT_STYLE: &str = "
+body { font-family: system-ui, sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; color: #222; }
+header { border-bottom: 2px solid #c33; margin-bottom: 1em; }
+h1 { margin-bottom: 0.2em; }
+.aliases { color: #666; }
+details { margin: 0.5em 0; border: 1px solid #ddd; border-radius: 4px; padding: 0.3em 0.8em; }
+summary { cursor: pointer; font-weight: bold; }
+table { border-collapse: collapse; width: 100%; margin: 0.5em 0; }
+th, td { text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid #eee; }
+a { color: #1a5fb4; }
+section + section { border-top: 1px solid #ccc; margin-top: 2em; }
+";
+
+// `apt --format html`: one complete page, every group in its own section, all text escaped
+fn render_groups_html(groups: &[&AttackObject], data: &AttackData, options: &DisplayOptions) -> String {
+    let names: Vec<&str> = groups.iter().map(|group| group.name.as_deref().unwrap_or("Unknown")).collect();
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{} - MITRE ATT&amp;CK</title>\n", html_escape(&names.join(", "))));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_REPORT_STYLE));
+    
+    for group in groups {
+        html.push_str("<section>\n<header>\n");
+        let name = html_escape(group.name.as_deref().unwrap_or("Unknown"));
+        match (get_mitre_id(group), attack_url(group)) {
+            (Some(mitre_id), Some(url)) => html.push_str(&format!(
+                "<h1>{} (<a href=\"{}\">{}</a>)</h1>\n", name, html_escape(&url), html_escape(&mitre_id)
+            )),
+            (Some(mitre_id), None) => html.push_str(&format!("<h1>{} ({})</h1>\n", name, html_escape(&mitre_id))),
+            (None, _) => html.push_str(&format!("<h1>{}</h1>\n", name)),
+        }
+        if let Some(aliases) = group.aliases.as_ref().filter(|aliases| !aliases.is_empty()) {
+            let aliases: Vec<String> = aliases.iter().map(|alias| html_escape(alias)).collect();
+            html.push_str(&format!("<p class=\"aliases\">Aliases: {}</p>\n", aliases.join(", ")));
+        }
+        html.push_str("</header>\n");
+        
+        if let Some(desc) = &group.description {
+            html.push_str("<h2>Description</h2>\n");
+            for paragraph in plain_description(desc).split("\n\n").map(str::trim).filter(|paragraph| !paragraph.is_empty()) {
+                html.push_str(&format!("<p>{}</p>\n", html_escape(paragraph)));
+            }
+        }
+        
+        let (mut techniques, via) = group_technique_footprint(group, data, options);
+        techniques.retain(|technique| matches_platforms(technique, &options.platforms, options.platform_match));
+        if !techniques.is_empty() {
+            html.push_str(&format!("<h2>Techniques ({})</h2>\n", techniques.len()));
+        }
+        for (tactic, mut tactic_techniques) in group_techniques_in_order(&techniques, options.tactic_order, data) {
+            sort_objects(&mut tactic_techniques, options.sort);
+            let total = tactic_techniques.len();
+            let hidden = apply_limit(&mut tactic_techniques, options.technique_limit);
+            html.push_str(&format!("<details open>\n<summary>{} ({})</summary>\n", html_escape(&tactic), total));
+            html.push_str("<table>\n<tr><th>ID</th><th>Technique</th></tr>\n");
+            for technique in tactic_techniques {
+                let mitre_id = html_escape(&get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string()));
+                let id_cell = match attack_url(technique) {
+                    Some(url) => format!("<a href=\"{}\">{}</a>", html_escape(&url), mitre_id),
+                    None => mitre_id,
+                };
+                let name = format!("{}{}", technique.name.as_deref().unwrap_or("Unknown"), via_suffix(&via, technique));
+                html.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", id_cell, html_escape(&name)));
+            }
+            html.push_str("</table>\n");
+            if hidden > 0 {
+                html.push_str(&format!("<p>… +{} more in this tactic</p>\n", hidden));
+            }
+            html.push_str("</details>\n");
+        }
+        html.push_str("</section>\n");
+    }
+    
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn print_separator() {
+    println!("{}", "─".repeat(80).bright_black());
+}
+
+fn print_deprecated_banner(obj: &AttackObject) {
+    if obj.is_deprecated() {
+        println!("{}", "DEPRECATED: MITRE no longer maintains this entry".red().bold());
+    }
+}
+
+// A description as printed: with --brief its first sentence (citations dropped), else in full
+fn description_text(desc: &str, citations: &mut CitationResolver, options: &DisplayOptions) -> String {
+    if options.brief {
+        first_sentence(desc, usize::MAX)
+    } else {
+        citations.resolve(desc)
+    }
+}
+
+fn print_technique_info(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    print_deprecated_banner(obj);
+    let name = highlight_match(obj.name.as_deref().unwrap_or("Unknown"), options.highlight.as_deref(), |text| text.bright_cyan().bold());
+    println!("{}{}", "Name: ".bright_cyan().bold(), name);
+    
+    if let Some(mitre_id) = get_mitre_id(obj) {
+        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    if obj.is_subtechnique == Some(true)
+        && let Some(parent) = get_parent_technique(&obj.id, data) {
+        let parent_id = get_mitre_id(parent).unwrap_or_else(|| "N/A".to_string());
+        let parent_name = parent.name.as_deref().unwrap_or("Unknown");
+        println!("{}", format!("Parent Technique: [{}] {}", parent_id, parent_name).bright_yellow());
+    }
+    let previous = previous_names(obj);
+    if !previous.is_empty() {
+        println!("{}", format!("Previously: {}", previous.join(", ")).bright_yellow());
+    }
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&description_text(desc, &mut citations, options), options.width));
+    }
+    
+    if let Some(tactics) = &obj.kill_chain_phases {
+        println!("\n{}", "Tactics:".bright_white().bold());
+        for tactic in tactics {
+            if tactic.is_attack() {
+                println!("  • {}", tactic_label(&tactic.phase_name, &tactic.phase_name));
+            }
+        }
+    }
+    
+    if let Some(platforms) = &obj.platforms {
+        println!("\n{}", "Platforms:".bright_white().bold());
+        for platform in platforms {
+            println!("  • {}", platform.bright_blue());
+        }
+    }
+    
+    if let Some(perms) = &obj.permissions_required {
+        println!("\n{}", "Permissions Required:".bright_white().bold());
+        for perm in perms {
+            println!("  • {}", perm.bright_red());
+        }
+    }
+    
+    if let Some(perms) = &obj.effective_permissions {
+        println!("\n{}", "Effective Permissions:".bright_white().bold());
+        for perm in perms {
+            println!("  • {}", perm.bright_red());
+        }
+    }
+    
+    if let Some(defenses) = &obj.defense_bypassed {
+        println!("\n{}", "Defense Bypassed:".bright_white().bold());
+        for defense in defenses {
+            println!("  • {}", defense.bright_yellow());
+        }
+    }
+    
+    if let Some(requirements) = &obj.system_requirements {
+        println!("\n{}", "System Requirements:".bright_white().bold());
+        for requirement in requirements {
+            println!("  • {}", requirement.bright_blue());
+        }
+    }
+    
+    if let Some(remote_support) = obj.remote_support {
+        println!("\n{} {}", "Remote Support:".bright_white().bold(), if remote_support { "yes" } else { "no" });
+    }
+    
+    if let Some(impact_types) = &obj.impact_type {
+        println!("\n{}", "Impact Type:".bright_white().bold());
+        for impact_type in impact_types {
+            println!("  • {}", impact_type.bright_red());
+        }
+    }
+    
+    if let Some(detection) = &obj.detection {
+        println!("\n{}", "Detection:".bright_white().bold());
+        println!("{}", wrap_text(&citations.resolve(detection), options.width));
+    }
+    
+    if let Some(data_sources) = &obj.data_sources {
+        println!("\n{}", "Data Sources:".bright_white().bold());
+        for source in data_sources {
+            println!("  • {}", source.bright_cyan());
+        }
+    }
+    
+    let mut components = get_detecting_components(&obj.id, data);
+    if !components.is_empty() {
+        println!("\n{}", "Detected By (Data Components):".bright_white().bold());
+        components.sort_by_key(|(component, source)| (source.and_then(|source| source.name.clone()), component.name.clone()));
+        
+        for (component, source) in components {
+            let component_name = component.name.as_deref().unwrap_or("Unknown");
+            match source {
+                Some(source) => {
+                    let source_id = get_mitre_id(source).unwrap_or_else(|| "N/A".to_string());
+                    println!(
+                        "  • {} {}",
+                        format!("{}: {}", source.name.as_deref().unwrap_or("Unknown"), component_name).bright_cyan(),
+                        format!("[{}]", source_id).bright_green()
+                    );
+                },
+                None => println!("  • {}", component_name.bright_cyan()),
+            }
+        }
+    }
+    
+    let mut subtechniques: Vec<(String, &AttackObject)> = get_subtechniques(&obj.id, data)
+        .into_iter()
+        .filter(|sub| matches_platforms(sub, &options.platforms, options.platform_match))
+        .map(|sub| (get_mitre_id(sub).unwrap_or_else(|| "N/A".to_string()), sub))
+        .collect();
+    if !subtechniques.is_empty() {
+        println!("\n{}", "Sub-techniques:".bright_white().bold());
+        subtechniques.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        for (mitre_id, sub) in subtechniques {
+            if let Some(sub_name) = &sub.name {
+                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), sub_name.bright_white());
+            }
+        }
+    }
+    
+    let linked = get_linked_techniques(&obj.id, data);
+    if !linked.is_empty() {
+        println!("\n{}", "Related Techniques:".bright_white().bold());
+        for technique in linked {
+            let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+            println!("  {} {}", format!("[{}]", mitre_id).bright_green(), technique.name.as_deref().unwrap_or("Unknown").bright_white());
+        }
+    }
+    
+    // Show which groups use this technique
+    let related_groups = get_related_groups(&obj.id, data);
+    if !related_groups.is_empty() {
+        println!("\n{}", "Used by Groups:".bright_white().bold());
+        let mut sorted_groups = related_groups;
+        sorted_groups.sort_by(|(a, _), (b, _)| {
+            a.name.as_ref().unwrap_or(&"".to_string())
+                .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
+        });
+        
+        for (group, relationship) in sorted_groups {
+            if let Some(group_name) = &group.name {
+                let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), group_name.bright_white());
+                
+                // The procedure example: how this group used the technique
+                if let Some(procedure) = relationship.description.as_deref().filter(|text| !text.is_empty()) {
+                    let procedure = match options.procedure_length {
+                        0 => procedure.to_string(),
+                        length => truncate_with_ellipsis(procedure, length),
+                    };
+                    println!("      {}", procedure.bright_black());
+                }
+            }
+        }
+    }
+    
+    let mut mitigations = get_mitigations_for_technique(&obj.id, data);
+    if !mitigations.is_empty() {
+        println!("\n{}", "Mitigations:".bright_white().bold());
+        mitigations.sort_by(|(a, _), (b, _)| compare_mitre_ids(&get_mitre_id(a).unwrap_or_default(), &get_mitre_id(b).unwrap_or_default()));
+        
+        for (mitigation, relationship) in mitigations {
+            let mitre_id = get_mitre_id(mitigation).unwrap_or_else(|| "N/A".to_string());
+            println!("  {} {}", format!("[{}]", mitre_id).bright_green(), mitigation.name.as_deref().unwrap_or("Unknown").bright_white());
+            
+            // Guidance specific to this technique, as opposed to the mitigation's general description
+            if let Some(guidance) = relationship.description.as_deref().filter(|text| !text.is_empty()) {
+                let guidance = match options.procedure_length {
+                    0 => guidance.to_string(),
+                    length => truncate_with_ellipsis(guidance, length),
+                };
+                println!("      {}", guidance.bright_black());
+            }
+        }
+    }
+    
+    print_contributors(obj);
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+// Terminals known not to understand OSC 8 escapes, which would show up as garbage
+fn terminal_supports_hyperlinks() -> bool {
+    match std::env::var("TERM") {
+        Ok(term) => !term.is_empty() && term != "dumb" && !term.starts_with("linux") && !term.starts_with("vt"),
+        Err(_) => false,
+    }
+}
+
+// `text` as an OSC 8 hyperlink to `url`; control characters are dropped so a URL can't end the sequence early
+fn hyperlink(url: &str, text: &str) -> String {
+    let url: String = url.chars().filter(|c| !c.is_control()).collect();
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+// "Source - URL", or just the source name linking to the URL
+fn reference_line(source_name: &str, url: &str, hyperlinks: bool) -> String {
+    if hyperlinks {
+        hyperlink(url, &source_name.bright_green().underline().to_string())
+    } else {
+        format!("{} - {}", source_name.bright_green(), url.bright_blue().underline())
+    }
+}
+
+fn print_references(obj: &AttackObject, options: &DisplayOptions) {
+    if options.hide_references {
+        return;
+    }
+    if let Some(refs) = &obj.external_references {
+        println!("\n{}", "References:".bright_white().bold());
+        for ref_obj in refs {
+            if let Some(url) = &ref_obj.url {
+                println!("  • {}", reference_line(&ref_obj.source_name, url, options.hyperlinks));
+            }
+        }
+    }
+}
+
+// The people and organizations MITRE credits for the object's content
+fn print_contributors(obj: &AttackObject) {
+    if let Some(contributors) = obj.contributors.as_ref().filter(|contributors| !contributors.is_empty()) {
+        println!("\n{}", "Contributors:".bright_white().bold());
+        for contributor in contributors {
+            println!("  • {}", contributor.bright_magenta());
+        }
+    }
+}
+
+// The apt --mitigations report: mitigations ranked by how many of the group's techniques they cover
+fn print_group_mitigations(group: &AttackObject, ranking: &[MitigationCoverage], technique_count: usize) {
+    let group_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+    println!("{}", format!("Mitigations for {} ({}):", group.name.as_deref().unwrap_or("Unknown"), group_id).bright_cyan().bold());
+    print_separator();
+    
+    if ranking.is_empty() {
+        println!("{}", "No mitigations address this group's techniques".yellow());
+        return;
+    }
+    for entry in ranking {
+        let mitre_id = get_mitre_id(entry.mitigation).unwrap_or_else(|| "N/A".to_string());
+        let plural = if entry.covered.len() == 1 { "" } else { "s" };
+        println!(
+            "{} {} {}",
+            format!("[{}]", mitre_id).bright_green(),
+            entry.mitigation.name.as_deref().unwrap_or("Unknown").bright_white(),
+            format!("— covers {} technique{}", entry.covered.len(), plural).bright_black()
+        );
+    }
+    
+    let covered: HashSet<&str> = ranking.iter().flat_map(|entry| entry.covered.iter().map(|technique| technique.id.as_str())).collect();
+    println!(
+        "\n{}",
+        format!("{} mitigations cover {} of the group's {} techniques", ranking.len(), covered.len(), technique_count).bright_cyan()
+    );
+}
+
+fn print_group_data_sources(group: &AttackObject, ranking: &[DataSourceCoverage], technique_count: usize) {
+    let group_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+    println!("{}", format!("Data Sources for {} ({}):", group.name.as_deref().unwrap_or("Unknown"), group_id).bright_cyan().bold());
+    print_separator();
+    
+    if ranking.is_empty() {
+        println!("{}", "None of this group's techniques list data sources".yellow());
+        return;
+    }
+    for entry in ranking {
+        let plural = if entry.covered.len() == 1 { "" } else { "s" };
+        println!(
+            "{} {}",
+            entry.data_source.bright_white(),
+            format!("— detects {} technique{}", entry.covered.len(), plural).bright_black()
+        );
+    }
+    
+    let covered: HashSet<&str> = ranking.iter().flat_map(|entry| entry.covered.iter().map(|technique| technique.id.as_str())).collect();
+    println!(
+        "\n{}",
+        format!("{} data sources cover {} of the group's {} techniques", ranking.len(), covered.len(), technique_count).bright_cyan()
+    );
+}
+
+fn print_group_info(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    print_deprecated_banner(obj);
+    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
+    
+    if let Some(mitre_id) = get_mitre_id(obj) {
+        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    let regions = group_regions(obj, data);
+    if !regions.is_empty() {
+        println!("{}", format!("Associated Region: {}", regions.join(", ")).bright_yellow());
+    }
+    
+    if let Some(aliases) = &obj.aliases {
+        println!("\n{}", "Aliases:".bright_white().bold());
+        for alias in aliases {
+            println!("  • {}", alias.bright_magenta());
+        }
+    }
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&description_text(desc, &mut citations, options), options.width));
+    }
+    
+    if options.profile {
+        print_group_software(obj, data);
+        print_group_campaigns(obj, data);
+    }
+    
+    // Find related techniques through relationships
+    let (techniques, via) = group_technique_footprint(obj, data, options);
+    print_used_techniques(&techniques, &via, data, options);
+    
+    let coverage = platform_coverage(&techniques);
+    if !coverage.is_empty() {
+        println!("\n{}", "Platform Coverage:".bright_white().bold());
+        for (platform, count) in coverage {
+            println!("  • {} {}", platform.bright_blue(), format!("({} of {} techniques)", count, techniques.len()).bright_black());
+        }
+    }
+    
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+fn print_group_software(group: &AttackObject, data: &AttackData) {
+    println!("\n{}", "Software Used:".bright_white().bold());
+    let software = get_group_software(&group.id, data);
+    if software.is_empty() {
+        println!("  {}", "None documented".bright_black());
+    }
+    for tool in software {
+        let mitre_id = get_mitre_id(tool).unwrap_or_else(|| "N/A".to_string());
+        println!(
+            "  {} {} {}",
+            format!("[{}]", mitre_id).bright_green(),
+            tool.name.as_deref().unwrap_or("Unknown").bright_white(),
+            format!("({})", tool.obj_type).bright_black()
+        );
+    }
+}
+
+// "2020-01-01 – 2021-01-01" from a campaign's first_seen/last_seen timestamps
+fn campaign_period(campaign: &AttackObject) -> String {
+    let date = |timestamp: &Option<String>| {
+        timestamp.as_deref().map_or("?", |timestamp| timestamp.get(..10).unwrap_or(timestamp)).to_string()
+    };
+    format!("{} – {}", date(&campaign.first_seen), date(&campaign.last_seen))
+}
+
+fn print_group_campaigns(group: &AttackObject, data: &AttackData) {
+    println!("\n{}", "Associated Campaigns:".bright_white().bold());
+    let campaigns = get_group_campaigns(&group.id, data);
+    if campaigns.is_empty() {
+        println!("  {}", "None documented".bright_black());
+    }
+    for campaign in campaigns {
+        let mitre_id = get_mitre_id(campaign).unwrap_or_else(|| "N/A".to_string());
+        println!(
+            "  {} {} {}",
+            format!("[{}]", mitre_id).bright_green(),
+            campaign.name.as_deref().unwrap_or("Unknown").bright_white(),
+            format!("({})", campaign_period(campaign)).bright_black()
+        );
+    }
+}
+
+fn print_software_info(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
+    
+    if let Some(mitre_id) = get_mitre_id(obj) {
+        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    if let Some(aliases) = &obj.software_aliases {
+        println!("\n{}", "Aliases:".bright_white().bold());
+        for alias in aliases {
+            println!("  • {}", alias.bright_magenta());
+        }
+    }
+    
+    if let Some(platforms) = &obj.platforms {
+        println!("\n{}", "Platforms:".bright_white().bold());
+        for platform in platforms {
+            println!("  • {}", platform.bright_blue());
+        }
+    }
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&citations.resolve(desc), options.width));
+    }
+    
+    let mut related_groups = get_related_groups(&obj.id, data);
+    if !related_groups.is_empty() {
+        println!("\n{}", "Used by Groups:".bright_white().bold());
+        related_groups.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+        
+        for (group, _) in related_groups {
+            if let Some(group_name) = &group.name {
+                let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), group_name.bright_white());
+            }
+        }
+    }
+    
+    // Find related techniques through relationships
+    print_used_techniques(&get_related_techniques(&obj.id, data), &HashMap::new(), data, options);
+    
+    print_contributors(obj);
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+fn print_campaign_info(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
+    
+    if let Some(mitre_id) = get_mitre_id(obj) {
+        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    if let Some(first_seen) = &obj.first_seen {
+        println!("{}", format!("First Seen: {}", first_seen).bright_yellow());
+    }
+    
+    if let Some(last_seen) = &obj.last_seen {
+        println!("{}", format!("Last Seen: {}", last_seen).bright_yellow());
+    }
+    
+    let attributed_groups = dedupe_by_id(data.related_targets(&obj.id, "attributed-to")
+        .filter(|target| target.obj_type == "intrusion-set")
+        .collect());
+    if !attributed_groups.is_empty() {
+        println!("\n{}", "Attributed To:".bright_white().bold());
+        for group in attributed_groups {
+            if let Some(group_name) = &group.name {
+                let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), group_name.bright_white());
+            }
+        }
+    }
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&citations.resolve(desc), options.width));
+    }
+    
+    // Find related techniques through relationships
+    print_used_techniques(&get_related_techniques(&obj.id, data), &HashMap::new(), data, options);
+    
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+fn print_mitigation_info(obj: &AttackObject, data: &AttackData, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    println!("{}", format!("Name: {}", obj.name.as_ref().unwrap_or(&"Unknown".to_string())).bright_cyan().bold());
+    
+    if let Some(mitre_id) = get_mitre_id(obj) {
+        println!("{}", format!("MITRE ID: {}", mitre_id).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&citations.resolve(desc), options.width));
+    }
+    
+    let mut techniques: Vec<(String, &AttackObject)> = get_mitigated_techniques(&obj.id, data)
+        .into_iter()
+        .map(|technique| (get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string()), technique))
+        .collect();
+    if !techniques.is_empty() {
+        println!("\n{}", "Mitigated Techniques:".bright_white().bold());
+        techniques.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        for (mitre_id, technique) in &techniques {
+            if let Some(tech_name) = &technique.name {
+                println!("  {} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
+            }
+        }
+        
+        println!("\n{}", format!("Total Techniques: {}", techniques.len()).bright_cyan());
+    }
+    
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+fn print_tactic_info(obj: &AttackObject, options: &DisplayOptions) {
+    let mut citations = CitationResolver::new(obj, options);
+    let name = obj.name.as_deref().unwrap_or("Unknown");
+    let shortname = obj.shortname.as_deref().unwrap_or(name);
+    println!("{} {}", "Name:".bright_cyan().bold(), tactic_label(shortname, name).bold());
+    
+    if let Some(shortname) = &obj.shortname {
+        println!("{}", format!("Short Name: {}", shortname).bright_green());
+    }
+    
+    println!("{}", format!("Type: {}", obj.obj_type).bright_yellow());
+    
+    if let Some(desc) = &obj.description {
+        println!("\n{}", "Description:".bright_white().bold());
+        println!("{}", wrap_text(&citations.resolve(desc), options.width));
+    }
+    
+    citations.print_footnotes();
+    print_references(obj, options);
+}
+
+// How far back `recent` looks when --since is not given
+const RECENT_DEFAULT_DAYS: u64 = 90;
+
+// Accepts a YYYY-MM-DD calendar date
+fn parse_date(value: &str) -> Result<String, String> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let numbers: Vec<u32> = parts.iter().filter_map(|part| part.parse().ok()).collect();
+    let valid = matches!(parts.as_slice(), [year, month, day] if year.len() == 4 && month.len() == 2 && day.len() == 2)
+        && numbers.len() == 3
+        && (1..=12).contains(&numbers[1])
+        && (1..=31).contains(&numbers[2]);
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!("expected a date as YYYY-MM-DD, found '{}'", value))
+    }
+}
+
+// Today's date minus `days`, as YYYY-MM-DD in UTC
+fn days_ago(days: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let day_number = (now / 86_400).saturating_sub(days) as i64;
+    
+    // Gregorian calendar from a day count, after Howard Hinnant's civil_from_days
+    let shifted = day_number + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// One "[ID] Name" line per object, under a heading with the count
+fn print_diff_section(title: &str, objects: &[&AttackObject]) {
+    println!("\n{}", format!("{} ({}):", title, objects.len()).bright_white().bold());
+    for obj in objects {
+        let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+        println!("  {} {}", format!("[{}]", mitre_id).bright_green(), obj.name.as_deref().unwrap_or("Unknown").bright_white());
+    }
+}
+
+fn print_release_diff(diff: &ReleaseDiff) {
+    println!("{}", "Matrix Changes:".bright_cyan().bold());
+    print_separator();
+    print_diff_section("Added", &diff.added);
+    print_diff_section("Removed", &diff.removed);
+    print_diff_section("Newly Deprecated", &diff.deprecated);
+    print_diff_section("Newly Revoked", &diff.revoked);
+    
+    println!("\n{}", format!("Version Changes ({}):", diff.version_bumps.len()).bright_white().bold());
+    for (old, new) in &diff.version_bumps {
+        let mitre_id = get_mitre_id(new).unwrap_or_else(|| "N/A".to_string());
+        println!(
+            "  {} {} {}",
+            format!("[{}]", mitre_id).bright_green(),
+            new.name.as_deref().unwrap_or("Unknown").bright_white(),
+            format!("(v{} → v{})", old.version.as_deref().unwrap_or("?"), new.version.as_deref().unwrap_or("?")).bright_black()
+        );
+    }
+}
+
+// --count-only: just the number, with a failing exit status when there is nothing to count
+fn print_count(count: usize) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", count);
+    if count == 0 {
+        return Err(QuietFailure.into());
+    }
+    Ok(())
+}
+
+// Reads one ID per line, skipping blank lines and # comments
+fn read_id_list(path: &Path) -> std::io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+// Hands a URL to the platform's default opener
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    
+    let status = command
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("opener exited with {}", status)))
+    }
+}
+
+// `matrix.json` -> `matrix.json.<suffix>`, next to the matrix so renames stay on one filesystem
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}", file_name, suffix))
+}
+
+// Downloads with curl (which draws the progress bar) into a temporary file, checks it parses
+// as the right matrix, then renames it over the old one so a failed update never leaves a
+// half-written matrix behind. The replaced file is kept as <matrix>.bak for --rollback.
+fn update_matrix(path: &Path, domain: Domain) -> Result<(), Box<dyn std::error::Error>> {
+    let url = domain.download_url();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let download = sibling_path(path, "download");
+    
+    println!("Downloading {}", url.bright_white());
+    let status = std::process::Command::new("curl")
+        .args(["--fail", "--location", "--progress-bar", "--output"])
+        .arg(&download)
+        .arg(&url)
+        .status()
+        .map_err(|err| format!("could not run curl ({}); it is needed to download the matrix", err))?;
+    if !status.success() {
+        let _ = fs::remove_file(&download);
+        return Err(format!("download failed: curl exited with {}", status).into());
+    }
+    
+    let data = match load_attack_data(&download) {
+        Ok(data) if data.matches_domain(domain) => data,
+        Ok(_) => {
+            let _ = fs::remove_file(&download);
+            return Err(format!("downloaded file is not the {} matrix", domain.stix_name()).into());
+        },
+        Err(err) => {
+            let _ = fs::remove_file(&download);
+            return Err(format!("downloaded file is not a valid matrix: {}", err).into());
+        },
+    };
+    
+    let backup = sibling_path(path, "bak");
+    let had_previous = path.exists();
+    if had_previous {
+        fs::copy(path, &backup)?;
+    }
+    fs::rename(&download, path)?;
+    remove_cache(path);
+    
+    let version = attack_version(&data).unwrap_or("unknown");
+    println!("{}", format!("Updated {} to ATT&CK v{}", path.display(), version).bright_green());
+    if had_previous {
+        println!("Previous matrix kept at {} (restore it with attcli update --rollback)", backup.display());
+    }
+    Ok(())
+}
+
+// The replaced matrix's cache would only be ignored as stale, so it is tidied away
+fn remove_cache(matrix_path: &Path) {
+    if let Ok(cache) = cache_path(matrix_path) {
+        let _ = fs::remove_file(cache);
+    }
+}
+
+fn rollback_matrix(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let backup = sibling_path(path, "bak");
+    if !backup.exists() {
+        return Err(format!("no backup at {} to roll back to", backup.display()).into());
+    }
+    fs::rename(&backup, path)?;
+    remove_cache(path);
+    println!("{}", format!("Restored {} from {}", path.display(), backup.display()).bright_green());
+    Ok(())
+}
+
+// Relationship types in the order the relationships command lists them; anything else follows alphabetically
+const RELATIONSHIP_TYPE_ORDER: [&str; 6] = ["uses", "mitigates", "subtechnique-of", "revoked-by", "detects", "attributed-to"];
+
+// "Name [MITRE ID]" for a relationship endpoint, falling back to the raw STIX id
+fn endpoint_label(stix_id: &str, data: &AttackData) -> String {
+    match data.get(stix_id) {
+        Some(obj) => {
+            let name = obj.name.as_deref().unwrap_or("Unknown");
+            match get_mitre_id(obj) {
+                Some(mitre_id) => format!("{} [{}]", name, mitre_id),
+                None => name.to_string(),
+            }
+        },
+        None => stix_id.to_string(),
+    }
+}
+
+fn print_relationships(obj: &AttackObject, relationships: &[&AttackObject], data: &AttackData) {
+    println!("{}", format!("Relationships for {}", endpoint_label(&obj.id, data)).bright_cyan().bold());
+    println!("{}", format!("STIX ID: {}", obj.id).bright_yellow());
+    
+    let mut by_type: HashMap<&str, Vec<&AttackObject>> = HashMap::new();
+    for relationship in relationships {
+        by_type.entry(relationship.relationship_type.as_deref().unwrap_or("unknown")).or_default().push(relationship);
+    }
+    let mut types: Vec<&str> = by_type.keys().copied().collect();
+    types.sort_by_key(|relationship_type| {
+        let position = RELATIONSHIP_TYPE_ORDER.iter().position(|known| known == relationship_type);
+        (position.unwrap_or(RELATIONSHIP_TYPE_ORDER.len()), *relationship_type)
+    });
+    
+    for relationship_type in types {
+        println!("\n{}", format!("{}:", relationship_type).bright_white().bold());
+        let mut lines: Vec<(String, String)> = by_type[relationship_type]
+            .iter()
+            .map(|relationship| (
+                endpoint_label(relationship.source_ref.as_deref().unwrap_or_default(), data),
+                endpoint_label(relationship.target_ref.as_deref().unwrap_or_default(), data),
+            ))
+            .collect();
+        lines.sort();
+        
+        for (source, target) in lines {
+            println!(
+                "  {} {} {}",
+                source.bright_white(),
+                format!("--[{}]-->", relationship_type).bright_magenta(),
+                target.bright_white()
+            );
+        }
+    }
+    
+    println!("\n{}", format!("Total Relationships: {}", relationships.len()).bright_cyan());
+}
+
+// Query used for the full-scan part of the benchmark workload
+const BENCHMARK_SEARCH_QUERY: &str = "process";
+
+fn benchmark_timing(name: &str, iterations: usize, elapsed: Duration) -> BenchmarkTiming {
+    let total_ms = elapsed.as_secs_f64() * 1000.0;
+    BenchmarkTiming {
+        name: name.to_string(),
+        iterations,
+        total_ms,
+        mean_us: if iterations > 0 { total_ms * 1000.0 / iterations as f64 } else { 0.0 },
+    }
+}
+
+fn run_benchmark(data: &AttackData, load_time: Duration, lookups: usize) -> BenchmarkReport {
+    let mut timings = vec![benchmark_timing("load", 1, load_time)];
+    
+    let start = Instant::now();
+    std::hint::black_box(Index::build_sequential(&data.objects));
+    timings.push(benchmark_timing("index_build", 1, start.elapsed()));
+    
+    #[cfg(feature = "parallel")]
+    {
+        let start = Instant::now();
+        std::hint::black_box(Index::build_parallel(&data.objects));
+        timings.push(benchmark_timing("index_build_parallel", 1, start.elapsed()));
+    }
+    
+    // Technique lookups by ID, picked with a fixed-seed xorshift so runs are comparable
+    let technique_ids: Vec<String> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .filter_map(get_mitre_id)
+        .collect();
+    
+    if !technique_ids.is_empty() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let start = Instant::now();
+        for _ in 0..lookups {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let id = &technique_ids[(state % technique_ids.len() as u64) as usize];
+            std::hint::black_box(find_technique_by_id(id, data));
+        }
+        timings.push(benchmark_timing("technique_lookup", lookups, start.elapsed()));
+    }
+    
+    let start = Instant::now();
+    std::hint::black_box(find_techniques_by_name(BENCHMARK_SEARCH_QUERY, data));
+    timings.push(benchmark_timing("technique_search", 1, start.elapsed()));
+    
+    // Resolve and bucket every group's techniques, as `apt` does before printing
+    let groups: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "intrusion-set")
+        .collect();
+    
+    let start = Instant::now();
+    for group in &groups {
+        let related_techniques = get_related_techniques(&group.id, data);
+        std::hint::black_box(group_techniques_by_tactic(&related_techniques));
+    }
+    timings.push(benchmark_timing("group_detail", groups.len(), start.elapsed()));
+    
+    BenchmarkReport {
+        objects: data.objects.len(),
+        timings,
+    }
+}
+
+fn print_benchmark_report(report: &BenchmarkReport) {
+    println!("{}", "Benchmark Results:".bright_cyan().bold());
+    print_separator();
+    println!("{}", format!("Objects loaded: {}", report.objects).bright_white());
+    println!();
+    
+    for timing in &report.timings {
+        println!(
+            "{} {} {}",
+            format!("{:<22}", timing.name).bright_green(),
+            format!("{:>12.3} ms", timing.total_ms).bright_white(),
+            format!("({} iterations, {:.3} µs each)", timing.iterations, timing.mean_us).bright_black()
+        );
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    
+    let paging = !cli.no_pager
+        && cli.output.is_none()
+        && !matches!(cli.command, Commands::Update { .. })
+        && std::io::stdout().is_terminal();
+    LazyLock::force(&TERMINAL_SIZE);
+    let pager = if paging { Pager::start() } else { None };
+    let result = run(&cli, pager.is_some());
+    if let Some(pager) = pager {
+        pager.finish();
+    }
+    
+    match result {
+        Ok(()) => {
+            if let Some(path) = &cli.output {
+                eprintln!("Wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        },
+        Err(err) if err.is::<QuietFailure>() => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{}", format!("Error: {}", err).red());
+            if let Some(AttError::MatrixNotFound(path)) = err.downcast_ref::<AttError>() {
+                eprintln!("{}", "Please run the installation script or `attcli update` first, or point --file or ATTCLI_MATRIX at a matrix.".yellow());
+                // A default location: list the whole search order, not just the path a new download would go to
+                let searched = Domain::ALL.into_iter().find_map(|domain| {
+                    Some((domain, matrix_candidates(domain).ok().filter(|candidates| candidates.contains(path))?))
+                });
+                if let Some((domain, candidates)) = searched {
+                    eprintln!("{}", format!("Looked for the {} matrix (from https://github.com/mitre-attack/attack-stix-data) at, in order:", domain.stix_name()).yellow());
+                    for candidate in candidates {
+                        eprintln!("  {}", candidate.display());
+                    }
+                }
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// `paging` means stdout is captured for the pager, and so ends up on the terminal after all
+fn run(cli: &Cli, paging: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = &cli.output
+        && !writes_own_output(&cli.command) {
+        redirect_stdout(path).map_err(|err| format!("could not write to {}: {}", path.display(), err))?;
+    }
+    
+    // Completions describe the CLI itself, so they must work before a matrix is installed
+    if let Commands::Completions { shell } = &cli.command {
+        print!("{}", completions::generate(*shell, Cli::command()));
+        return Ok(());
+    }
+    if let Commands::ConfigPath = &cli.command {
+        println!("{}", Config::path()?.display());
+        return Ok(());
+    }
+    
+    // Without a home directory there is no config file, but --file and $ATTCLI_MATRIX still work
+    let config_path = Config::path().ok();
+    let config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if cli.no_color || cli.json || cli.json_lines || cli.output.is_some() || no_color_env || config.color == Some(false) {
+        colored::control::set_override(false);
+    } else if config.color == Some(true) || paging {
+        colored::control::set_override(true);
+    } else if !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+    let default_format = match &config.format {
+        Some(name) => OutputFormat::from_str(name, true)
+            .map_err(|_| format!("invalid format '{}' in {}", name, config_path.as_deref().unwrap_or(Path::new("config.toml")).display()))?,
+        None => OutputFormat::Text,
+    };
+    
+    let options = DisplayOptions {
+        resolve_citations: cli.resolve_citations,
+        procedure_length: cli.procedure_length,
+        brief: cli.brief,
+        hide_references: cli.no_references || config.references == Some(false),
+        // Hyperlinks are escape sequences too, so --no-color, NO_COLOR and the like turn them off as well
+        hyperlinks: !cli.no_hyperlinks
+            && colored::control::SHOULD_COLORIZE.should_colorize()
+            && (paging || std::io::stdout().is_terminal())
+            && terminal_supports_hyperlinks(),
+        width: cli.width.unwrap_or_else(get_terminal_width),
+        ..DisplayOptions::default()
+    };
+    // Diff reads its own two files, so it works without an installed matrix
+    if let Commands::Diff { old_file, new_file } = &cli.command {
+        let old = load_attack_data(old_file)?;
+        let new = load_attack_data(new_file)?;
+        print_release_diff(&diff_releases(&old, &new));
+        return Ok(());
+    }
+    
+    let matrix_path = resolve_matrix_path(cli.file.as_deref(), cli.domain, &config)?;
+    if let Commands::CacheClear = &cli.command {
+        let cache = cache_path(&matrix_path)?;
+        match fs::remove_file(&cache) {
+            Ok(()) => println!("{}", format!("Removed {}", cache.display()).bright_green()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => println!("No cache at {}", cache.display()),
+            Err(err) => return Err(err.into()),
+        }
+        return Ok(());
+    }
+    
+    let domain = cli.domain.or(config.domain).unwrap_or_default();
+    if let Commands::Update { rollback } = &cli.command {
+        return if *rollback {
+            rollback_matrix(&matrix_path)
+        } else {
+            update_matrix(&matrix_path, domain)
+        };
+    }
+    let load_start = Instant::now();
+    let cache = if cli.no_cache { None } else { cache_path(&matrix_path).ok() };
+    let data = match cache.as_deref().and_then(|cache| read_cache(cache, &matrix_path)) {
+        Some(data) => data,
+        None => {
+            let data = load_attack_data(&matrix_path)?;
+            if let Some(cache) = &cache
+                && let Err(err) = write_cache(cache, &data) {
+                eprintln!("{}", format!("Warning: could not write cache {}: {}", cache.display(), err).yellow());
+            }
+            data
+        },
+    };
+    let load_time = load_start.elapsed();
+    
+    if (cli.domain.is_some() || config.domain.is_some()) && !data.matches_domain(domain) {
+        eprintln!(
+            "{}",
+            format!("Warning: {} does not look like the {} matrix", matrix_path.display(), domain.stix_name()).yellow()
+        );
+    }
+
+    match &cli.command {
+        Commands::AptList { sort, aliases, limit, count_only, deprecated_only } => {
+            let mut groups: Vec<&AttackObject> = data.objects
+                .iter()
                 .filter(|obj| obj.obj_type == "intrusion-set")
+                .filter(|obj| if *deprecated_only { obj.is_sunset() } else { cli.include_deprecated || !obj.is_deprecated() })
+                .collect();
+            if *count_only {
+                return print_count(groups.len());
+            }
+            
+            // Only worked out when sorting by it, as it walks every group's relationships
+            let technique_counts: HashMap<&str, usize> = if *sort == GroupSort::TechniqueCount {
+                groups.iter().map(|group| (group.id.as_str(), get_related_techniques(&group.id, &data).len())).collect()
+            } else {
+                HashMap::new()
+            };
+            match sort {
+                GroupSort::Name => sort_objects(&mut groups, SortOrder::Name),
+                GroupSort::Id => sort_objects(&mut groups, SortOrder::Id),
+                GroupSort::TechniqueCount => {
+                    sort_objects(&mut groups, SortOrder::Name);
+                    groups.sort_by_key(|group| std::cmp::Reverse(technique_counts[group.id.as_str()]));
+                },
+            }
+            let hidden = apply_limit(&mut groups, *limit);
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&groups)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&groups)?);
+                return Ok(());
+            }
+            
+            println!("{}", "APT Groups (Advanced Persistent Threat Groups):".bright_cyan().bold());
+            print_separator();
+            
+            for group in groups {
+                if let Some(name) = &group.name {
+                    let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+                    let count = technique_counts.get(group.id.as_str())
+                        .map(|count| format!(" ({} {})", count, if *count == 1 { "technique" } else { "techniques" }))
+                        .unwrap_or_default();
+                    let group_aliases = group.aliases.as_deref().unwrap_or_default();
+                    let alias_count = match aliases {
+                        AliasDisplay::Count => format!(" ({} {})", group_aliases.len(), if group_aliases.len() == 1 { "alias" } else { "aliases" }),
+                        AliasDisplay::Inline | AliasDisplay::Hidden => String::new(),
+                    };
+                    let sunset = if *deprecated_only { sunset_note(group, &data) } else { String::new() };
+                    println!(
+                        "{} {}{}{}{}",
+                        format!("[{}]", mitre_id).bright_green(),
+                        name.bright_white(),
+                        count.bright_cyan(),
+                        alias_count.bright_black(),
+                        sunset.bright_red()
+                    );
+                    
+                    if *aliases == AliasDisplay::Inline {
+                        if let Some(group_aliases) = &group.aliases {
+                            println!("  Aliases: {}", group_aliases.join(", ").bright_black());
+                        }
+                        println!();
+                    }
+                }
+            }
+            print_limit_notice(hidden);
+        },
+        
+        Commands::Apt {
+            name, exact, fuzzy, platforms, platform_match, format, sort, tactic_order, limit_techniques, wrap_width, highlight_platform, count, transitive, mitigations, data_sources, short, count_only,
+        } => {
+            let options = DisplayOptions {
+                platforms: platforms.clone(),
+                platform_match: *platform_match,
+                sort: *sort,
+                tactic_order: *tactic_order,
+                technique_limit: *limit_techniques,
+                highlight_platform: highlight_platform.clone(),
+                technique_count: *count,
+                transitive: *transitive,
+                ..options.clone()
+            };
+            let format = GroupFormat::resolve(cli.json, format.or(short.then_some(GroupFormat::Text)), default_format);
+            if matches!(format, GroupFormat::Markdown | GroupFormat::Mermaid | GroupFormat::Html) {
+                colored::control::set_override(false);
+            }
+            // The other formats list every technique in one table, graph or document, with no per-tactic lists to cap
+            if *limit_techniques > 0 && !matches!(format, GroupFormat::Text | GroupFormat::Markdown | GroupFormat::Html) {
+                return Err("--limit-techniques only applies to the text, markdown and html formats".into());
+            }
+            
+            let mut found_groups = match_names(*exact, *fuzzy, || find_groups_by_exact_name(name, &data), || find_groups_by_name(name, &data));
+            if !cli.no_follow_revoked {
+                found_groups = follow_revoked(found_groups, &data, matches!(format, GroupFormat::Json | GroupFormat::Yaml));
+            }
+            
+            if *count_only {
+                let techniques: HashSet<&str> = found_groups.iter()
+                    .flat_map(|group| group_technique_footprint(group, &data, &options).0)
+                    .filter(|technique| matches_platforms(technique, &options.platforms, options.platform_match))
+                    .filter(|technique| count.includes(technique))
+                    .map(|technique| technique.id.as_str())
+                    .collect();
+                return print_count(techniques.len());
+            }
+            
+            if *mitigations && !found_groups.is_empty() {
+                let reports: Vec<(&AttackObject, Vec<MitigationCoverage>, usize)> = found_groups.iter()
+                    .map(|group| {
+                        let (mut techniques, _) = group_technique_footprint(group, &data, &options);
+                        techniques.retain(|technique| matches_platforms(technique, &options.platforms, options.platform_match));
+                        (*group, rank_mitigations_for_techniques(&techniques, &data), techniques.len())
+                    })
+                    .collect();
+                
+                if cli.json {
+                    let json: Vec<serde_json::Value> = reports.iter()
+                        .map(|(group, ranking, _)| serde_json::json!({
+                            "group": get_mitre_id(group),
+                            "name": group.name,
+                            "mitigations": ranking.iter().map(|entry| serde_json::json!({
+                                "id": get_mitre_id(entry.mitigation),
+                                "name": entry.mitigation.name,
+                                "covers": entry.covered.iter().filter_map(|technique| get_mitre_id(technique)).collect::<Vec<_>>(),
+                            })).collect::<Vec<_>>(),
+                        }))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                    return Ok(());
+                }
+                
+                for (i, (group, ranking, technique_count)) in reports.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_group_mitigations(group, ranking, *technique_count);
+                }
+            } else if *data_sources && !found_groups.is_empty() {
+                let reports: Vec<(&AttackObject, Vec<DataSourceCoverage>, usize)> = found_groups.iter()
+                    .map(|group| {
+                        let (mut techniques, _) = group_technique_footprint(group, &data, &options);
+                        techniques.retain(|technique| matches_platforms(technique, &options.platforms, options.platform_match));
+                        (*group, rank_data_sources(&techniques), techniques.len())
+                    })
+                    .collect();
+                
+                if cli.json {
+                    let json: Vec<serde_json::Value> = reports.iter()
+                        .map(|(group, ranking, _)| serde_json::json!({
+                            "group": get_mitre_id(group),
+                            "name": group.name,
+                            "data_sources": ranking.iter().map(|entry| serde_json::json!({
+                                "data_source": entry.data_source,
+                                "covers": entry.covered.iter().filter_map(|technique| get_mitre_id(technique)).collect::<Vec<_>>(),
+                            })).collect::<Vec<_>>(),
+                        }))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                    return Ok(());
+                }
+                
+                for (i, (group, ranking, technique_count)) in reports.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_group_data_sources(group, ranking, *technique_count);
+                }
+            } else if format == GroupFormat::Json {
+                print_json_matches(&found_groups)?;
+            } else if format == GroupFormat::Yaml {
+                print_yaml_matches(&found_groups)?;
+            } else if found_groups.is_empty() {
+                println!("{}", format!("No APT group found matching '{}'", name).red());
+                print_suggestions(name, ObjectKind::Group, &data);
+            } else if format == GroupFormat::Mermaid {
+                let graphs: Vec<String> = found_groups.iter()
+                    .map(|obj| {
+                        let (mut techniques, via) = group_technique_footprint(obj, &data, &options);
+                        techniques.retain(|technique| matches_platforms(technique, &options.platforms, options.platform_match));
+                        build_mermaid_graph(obj, &techniques, &via)
+                    })
+                    .collect();
+                print!("{}", graphs.join("\n"));
+            } else if format == GroupFormat::Html {
+                print!("{}", render_groups_html(&found_groups, &data, &options));
+            } else if format == GroupFormat::Markdown {
+                let reports: Vec<String> = found_groups.iter()
+                    .map(|obj| render_group_markdown(obj, &data, &options))
+                    .collect();
+                println!("{}", reports.join("\n---\n\n").trim_end());
+            } else if format == GroupFormat::Table {
+                for (i, obj) in found_groups.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    let group_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+                    println!("{}", format!("{} ({})", obj.name.as_deref().unwrap_or("Unknown"), group_id).bright_cyan().bold());
+                    
+                    let (techniques, via) = group_technique_footprint(obj, &data, &options);
+                    let mut rows: Vec<Vec<String>> = techniques
+                        .into_iter()
+                        .filter(|technique| matches_platforms(technique, &options.platforms, options.platform_match))
+                        .map(|technique| vec![
+                            get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string()),
+                            format!("{}{}", technique.name.as_deref().unwrap_or_default(), via_suffix(&via, technique)),
+                            technique_tactics(technique).join(", "),
+                        ])
+                        .collect();
+                    rows.sort_by(|a, b| compare_mitre_ids(&a[0], &b[0]));
+                    print_table(&["ID", "Technique", "Tactics"], &rows, &[false, true, false], wrap_width.unwrap_or(options.width));
+                }
+            } else if *short {
+                found_groups.iter().for_each(|obj| print_short_summary(obj, None));
+            } else {
+                for (i, obj) in found_groups.iter().enumerate() {
+                    if i > 0 {
+                        print_separator();
+                    }
+                    print_group_info(obj, &data, &options);
+                }
+            }
+        },
+        
+        Commands::TechniqueId { id, platforms, platform_match, format, with_subs, short, tactics_only, prefix } => {
+            let options = DisplayOptions { platforms: platforms.clone(), platform_match: *platform_match, ..options.clone() };
+            let format = ObjectFormat::resolve(cli.json, format.or(short.then_some(ObjectFormat::Text)), default_format);
+            // `-` reads newline-separated IDs from stdin; unknown ones are reported on stderr and skipped
+            let batch = id == "-";
+            let ids = if batch { read_stdin_ids()? } else { vec![id.clone()] };
+            
+            let mut shown: Vec<&AttackObject> = Vec::new();
+            let mut missing = false;
+            // Several techniques from one prefix are listed one per line rather than in full
+            let mut listed = false;
+            for id in &ids {
+                if *prefix || id.ends_with('.') {
+                    let mut family = find_techniques_by_id_prefix(id, &data);
+                    family.retain(|obj| matches_platforms(obj, platforms, *platform_match));
+                    family.retain(|obj| cli.include_deprecated || !obj.is_deprecated());
+                    if family.is_empty() {
+                        let message = format!("No technique found with an ID starting with '{}'{}", id, platform_suffix(platforms, *platform_match));
+                        if batch || *tactics_only {
+                            eprintln!("{}", message.red());
+                        } else if format == ObjectFormat::Text {
+                            println!("{}", message.red());
+                        }
+                        missing = true;
+                    }
+                    listed |= family.len() > 1;
+                    shown.extend(family);
+                    continue;
+                }
+                
+                let mut found = find_technique_by_id(id, &data);
+                if !cli.no_follow_revoked {
+                    found = follow_revoked(found.into_iter().collect(), &data, format != ObjectFormat::Text || *tactics_only).pop();
+                }
+                let Some(technique) = found.filter(|obj| matches_platforms(obj, platforms, *platform_match)) else {
+                    let message = format!("No technique found with ID '{}'{}", id, platform_suffix(platforms, *platform_match));
+                    if batch || *tactics_only {
+                        eprintln!("{}", message.red());
+                    } else if format == ObjectFormat::Text {
+                        println!("{}", message.red());
+                    }
+                    missing = true;
+                    continue;
+                };
+                
+                shown.push(technique);
+                if *with_subs {
+                    let mut subtechniques = get_subtechniques(&technique.id, &data);
+                    subtechniques.retain(|sub| matches_platforms(sub, platforms, *platform_match));
+                    sort_objects(&mut subtechniques, SortOrder::Id);
+                    shown.extend(subtechniques);
+                }
+            }
+            
+            if *tactics_only {
+                for technique in &shown {
+                    for phase in technique.kill_chain_phases.iter().flatten().filter(|phase| phase.is_attack()) {
+                        println!("{}", phase.phase_name);
+                    }
+                }
+                if missing {
+                    return Err(QuietFailure.into());
+                }
+                return Ok(());
+            }
+            
+            // A batch is always a list, even when only one of its IDs was found
+            match format {
+                ObjectFormat::Json if batch => println!("{}", serde_json::to_string_pretty(&shown)?),
+                ObjectFormat::Yaml if batch => print!("{}", to_yaml(&serde_json::to_value(&shown)?)),
+                _ => {},
+            }
+            if (batch && format != ObjectFormat::Text) || print_structured_matches(&shown, format)? {
+                return Ok(());
+            }
+            
+            for (i, obj) in shown.iter().enumerate() {
+                if *short || listed {
+                    print_short_summary(obj, None);
+                    continue;
+                }
+                if i > 0 {
+                    print_separator();
+                }
+                print_technique_info(obj, &data, &options);
+            }
+        },
+        
+        Commands::TechniqueName { name, exact, fuzzy, no_dedupe, platforms, platform_match, limit, format, short, count_only } => {
+            let options = DisplayOptions {
+                platforms: platforms.clone(),
+                platform_match: *platform_match,
+                highlight: (!*exact || *fuzzy).then(|| name.clone()),
+                ..options.clone()
+            };
+            let format = ObjectFormat::resolve(cli.json, format.or(short.then_some(ObjectFormat::Text)), default_format);
+            let mut found = match_names(*exact, *fuzzy, || find_techniques_by_exact_name(name, &data), || find_techniques_by_name(name, &data));
+            if !*no_dedupe {
+                found = dedupe_by_id(found);
+            }
+            found.retain(|obj| matches_platforms(obj, platforms, *platform_match));
+            if *count_only {
+                return print_count(found.len());
+            }
+            sort_objects(&mut found, SortOrder::Name);
+            let hidden = apply_limit(&mut found, *limit);
+            
+            if print_structured_matches(&found, format)? {
+                return Ok(());
+            } else if found.is_empty() {
+                println!("{}", format!("No technique found matching '{}'{}", name, platform_suffix(platforms, *platform_match)).red());
+                print_suggestions(name, ObjectKind::Technique, &data);
+            } else {
+                for (i, obj) in found.iter().enumerate() {
+                    if *short {
+                        print_short_summary(obj, options.highlight.as_deref());
+                        continue;
+                    }
+                    if i > 0 {
+                        print_separator();
+                    }
+                    print_technique_info(obj, &data, &options);
+                }
+                print_limit_notice(hidden);
+            }
+        },
+        
+        Commands::TacticList { count_only } => {
+            let tactics = get_tactics_in_kill_chain_order(&data);
+            if *count_only {
+                return print_count(tactics.len());
+            }
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&tactics)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&tactics)?);
+                return Ok(());
+            }
+            
+            println!("{}", "Tactics (Kill-Chain Order):".bright_cyan().bold());
+            print_separator();
+            
+            let technique_counts = get_tactic_technique_counts(&data);
+            for tactic in tactics {
+                if let Some(tactic_name) = &tactic.name {
+                    let shortname = tactic.shortname.as_deref().unwrap_or_default();
+                    let count = technique_counts.get(shortname).copied().unwrap_or(0);
+                    println!(
+                        "{} {} {}",
+                        tactic_label(shortname, tactic_name),
+                        format!("({})", shortname).bright_black(),
+                        format!("({} techniques)", count).bright_cyan()
+                    );
+                }
+            }
+        },
+        
+        Commands::Tactic { name, format, tree } => {
+            let found_tactics = find_tactics_by_name(name, &data);
+            let shortnames: HashSet<String> = found_tactics.iter()
+                .filter_map(|tactic| tactic.shortname.as_deref().map(normalize_tactic_name))
+                .collect();
+            
+            if print_structured_matches(&found_tactics, ObjectFormat::resolve(cli.json, *format, default_format))? {
+                return Ok(());
+            }
+            
+            for (i, obj) in found_tactics.iter().enumerate() {
+                if i > 0 {
+                    print_separator();
+                }
+                print_tactic_info(obj, &options);
+            }
+            
+            // If we found a tactic, also show related techniques
+            if !found_tactics.is_empty() && *tree {
+                println!("\n{}", "Related Techniques:".bright_white().bold());
+                print_separator();
+                
+                let sequence = tactic_sequence(&data);
+                let position = |tactic: &AttackObject| {
+                    tactic.shortname.as_deref().and_then(|shortname| sequence.iter().position(|phase| *phase == shortname))
+                };
+                let mut ordered = found_tactics.clone();
+                ordered.sort_by_key(|tactic| position(tactic).unwrap_or(usize::MAX));
+                
+                for tactic in ordered {
+                    let Some(shortname) = tactic.shortname.as_deref() else { continue };
+                    let mut techniques = list_techniques(Some(shortname), &[], PlatformMatch::Any, true, &data);
+                    techniques.retain(|obj| cli.include_deprecated || !obj.is_deprecated());
+                    sort_objects(&mut techniques, SortOrder::Name);
+                    
+                    let tactic_name = tactic.name.as_deref().unwrap_or(shortname);
+                    let heading = match position(tactic) {
+                        Some(i) => format!("{}. {} ({}):", i + 1, tactic_name, techniques.len()),
+                        None => format!("{} ({}):", tactic_name, techniques.len()),
+                    };
+                    println!("\n{}", tactic_label(shortname, &heading).bold());
+                    for entry in nest_subtechniques(&techniques, &data) {
+                        for (indent, technique) in std::iter::once((2, entry.technique)).chain(entry.subtechniques.iter().map(|sub| (6, *sub))) {
+                            let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                            println!(
+                                "{}{} {}",
+                                " ".repeat(indent),
+                                format!("[{}]", mitre_id).bright_green(),
+                                technique.name.as_deref().unwrap_or("Unknown").bright_white()
+                            );
+                        }
+                    }
+                }
+            } else if !found_tactics.is_empty() {
+                println!("\n{}", "Related Techniques:".bright_white().bold());
+                print_separator();
+                
+                let mut techniques: Vec<&AttackObject> = data.objects
+                    .iter()
+                    .filter(|obj| {
+                        if obj.obj_type == "attack-pattern"
+                            && let Some(phases) = &obj.kill_chain_phases {
+                            return phases.iter().any(|phase| {
+                                phase.is_attack() && shortnames.contains(&normalize_tactic_name(&phase.phase_name))
+                            });
+                        }
+                        false
+                    })
+                    .filter(|obj| cli.include_deprecated || !obj.is_deprecated())
+                    .collect();
+                
+                techniques.sort_by(|a, b| {
+                    a.name.as_ref().unwrap_or(&"".to_string())
+                        .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
+                });
+                
+                for technique in techniques {
+                    if let Some(tech_name) = &technique.name {
+                        let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                        println!("{} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
+                    }
+                }
+            } else {
+                println!("{}", format!("No tactic found matching '{}'", name).red());
+                println!("\n{}", "Available tactics:".bright_white().bold());
+                
+                let mut tactics: Vec<&AttackObject> = data.objects
+                    .iter()
+                    .filter(|obj| obj.obj_type == "x-mitre-tactic")
+                    .collect();
+                
+                tactics.sort_by(|a, b| {
+                    a.name.as_ref().unwrap_or(&"".to_string())
+                        .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
+                });
+                
+                for tactic in tactics {
+                    if let Some(tactic_name) = &tactic.name {
+                        let shortname = tactic.shortname.as_ref()
+                            .map(|s| format!(" ({})", s))
+                            .unwrap_or_default();
+                        println!("  • {}{}", tactic_name.bright_cyan(), shortname.bright_black());
+                    }
+                }
+            }
+        },
+        
+        Commands::TechniqueList { tactic, platforms, platform_match, include_subs, count_only, deprecated_only } => {
+            let tactic_obj = match tactic {
+                Some(tactic) => match find_tactic(tactic, &data) {
+                    Some(found) => Some(found),
+                    None => return Err(format!("No tactic found matching '{}'", tactic).into()),
+                },
+                None => None,
+            };
+            
+            let techniques = if *deprecated_only {
+                list_sunset_techniques(tactic.as_deref(), platforms, *platform_match, *include_subs, &data)
+            } else {
+                let mut techniques = list_techniques(tactic.as_deref(), platforms, *platform_match, *include_subs, &data);
+                techniques.retain(|obj| cli.include_deprecated || !obj.is_deprecated());
+                techniques
+            };
+            if *count_only {
+                return print_count(techniques.len());
+            }
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&techniques)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&techniques)?);
+                return Ok(());
+            }
+            
+            match tactic_obj {
+                Some(obj) => {
+                    let name = obj.name.as_deref().unwrap_or("Unknown");
+                    println!("{} {}", "Techniques:".bright_cyan().bold(), tactic_label(obj.shortname.as_deref().unwrap_or(name), name));
+                },
+                None => println!("{}", "Techniques:".bright_cyan().bold()),
+            }
+            print_separator();
+            
+            for technique in &techniques {
+                let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                let sunset = if *deprecated_only { sunset_note(technique, &data) } else { String::new() };
+                println!(
+                    "{} {}{}",
+                    format!("[{}]", mitre_id).bright_green(),
+                    technique.name.as_deref().unwrap_or("Unknown").bright_white(),
+                    sunset.bright_red()
+                );
+            }
+            println!("\n{}", format!("{} techniques{}", techniques.len(), platform_suffix(platforms, *platform_match)).bright_cyan());
+        },
+        
+        Commands::SoftwareList { sort, limit, count_only, deprecated_only } => {
+            let mut software: Vec<&AttackObject> = data.objects
+                .iter()
+                .filter(|obj| is_software(obj))
+                .filter(|obj| if *deprecated_only { obj.is_sunset() } else { cli.include_deprecated || !obj.is_deprecated() })
+                .collect();
+            if *count_only {
+                return print_count(software.len());
+            }
+            
+            sort_objects(&mut software, *sort);
+            let hidden = apply_limit(&mut software, *limit);
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&software)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&software)?);
+                return Ok(());
+            }
+            
+            println!("{}", "Software (Malware and Tools):".bright_cyan().bold());
+            print_separator();
+            
+            for item in software {
+                if let Some(name) = &item.name {
+                    let mitre_id = get_mitre_id(item).unwrap_or_else(|| "N/A".to_string());
+                    let sunset = if *deprecated_only { sunset_note(item, &data) } else { String::new() };
+                    println!(
+                        "{} {} {}{}",
+                        format!("[{}]", mitre_id).bright_green(),
+                        name.bright_white(),
+                        format!("({})", item.obj_type).bright_yellow(),
+                        sunset.bright_red()
+                    );
+                    
+                    if let Some(aliases) = &item.software_aliases {
+                        let alias_str = aliases.join(", ");
+                        println!("  Aliases: {}", alias_str.bright_black());
+                    }
+                    println!();
+                }
+            }
+            print_limit_notice(hidden);
+        },
+        
+        Commands::Software { id_or_name, short } => {
+            let found = find_software(id_or_name, &data);
+            
+            if cli.json {
+                print_json_matches(&found)?;
+            } else if found.is_empty() {
+                println!("{}", format!("No software found matching '{}'", id_or_name).red());
+            } else if *short {
+                found.iter().for_each(|obj| print_short_summary(obj, None));
+            } else {
+                for (i, obj) in found.iter().enumerate() {
+                    if i > 0 {
+                        print_separator();
+                    }
+                    print_software_info(obj, &data, &options);
+                }
+            }
+        },
+        
+        Commands::CampaignList { count_only } => {
+            let mut campaigns: Vec<&AttackObject> = data.objects
+                .iter()
+                .filter(|obj| obj.obj_type == "campaign")
+                .filter(|obj| cli.include_deprecated || !obj.is_deprecated())
                 .collect();
+            if *count_only {
+                return print_count(campaigns.len());
+            }
             
-            groups.sort_by(|a, b| {
-                a.name.as_ref().unwrap_or(&"".to_string())
-                    .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
-            });
+            campaigns.sort_by(|a, b| a.name.cmp(&b.name));
             
-            for group in groups {
-                if let Some(name) = &group.name {
-                    let mitre_id = get_mitre_id(group).unwrap_or_else(|| "N/A".to_string());
+            if cli.json_lines {
+                print!("{}", json_lines(&campaigns)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&campaigns)?);
+                return Ok(());
+            }
+            
+            println!("{}", "Campaigns:".bright_cyan().bold());
+            print_separator();
+            
+            for campaign in campaigns {
+                if let Some(name) = &campaign.name {
+                    let mitre_id = get_mitre_id(campaign).unwrap_or_else(|| "N/A".to_string());
                     println!("{} {}", format!("[{}]", mitre_id).bright_green(), name.bright_white());
-                    
-                    if let Some(aliases) = &group.aliases {
-                        let alias_str = aliases.join(", ");
-                        println!("  Aliases: {}", alias_str.bright_black());
-                    }
-                    println!();
                 }
             }
         },
         
-        Commands::Apt { name } => {
-            let name_lower = name.to_lowercase();
-            let mut found_groups = Vec::new();
+        Commands::Campaign { id_or_name } => {
+            let found = find_campaigns(id_or_name, &data);
             
-            for obj in &data.objects {
-                if obj.obj_type == "intrusion-set" {
-                    let mut matched = false;
-                    
-                    // Check name
-                    if let Some(obj_name) = &obj.name {
-                        if obj_name.to_lowercase().contains(&name_lower) {
-                            matched = true;
-                        }
-                    }
-                    
-                    // Also check aliases
-                    if !matched {
-                        if let Some(aliases) = &obj.aliases {
-                            for alias in aliases {
-                                if alias.to_lowercase().contains(&name_lower) {
-                                    matched = true;
-                                    break;
-                                }
-                            }
-                        }
+            if cli.json {
+                print_json_matches(&found)?;
+            } else if found.is_empty() {
+                println!("{}", format!("No campaign found matching '{}'", id_or_name).red());
+            } else {
+                for (i, obj) in found.iter().enumerate() {
+                    if i > 0 {
+                        print_separator();
                     }
-                    
-                    if matched {
-                        found_groups.push(obj);
+                    print_campaign_info(obj, &data, &options);
+                }
+            }
+        },
+        
+        Commands::Mitigation { id, format, gaps } => {
+            let found = find_mitigation_by_id(id, &data);
+            if *gaps {
+                let Some(mitigation) = found else {
+                    return Err(format!("No mitigation found with ID '{}'", id).into());
+                };
+                let coverage = mitigation_gaps(&mitigation.id, &data);
+                
+                if cli.json {
+                    let tactics: Vec<serde_json::Value> = coverage.iter()
+                        .map(|entry| serde_json::json!({
+                            "tactic": entry.tactic,
+                            "covered": entry.covered(),
+                            "total": entry.total,
+                            "uncovered": entry.missing.iter().filter_map(|technique| get_mitre_id(technique)).collect::<Vec<_>>(),
+                        }))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                        "mitigation": get_mitre_id(mitigation),
+                        "name": mitigation.name,
+                        "tactics": tactics,
+                    }))?);
+                    return Ok(());
+                }
+                
+                let mitre_id = get_mitre_id(mitigation).unwrap_or_else(|| "N/A".to_string());
+                println!("{}", format!("Techniques not addressed by [{}] {}:", mitre_id, mitigation.name.as_deref().unwrap_or("Unknown")).bright_cyan().bold());
+                print_separator();
+                if coverage.is_empty() {
+                    println!("{}", "The mitigation addresses no current technique".yellow());
+                }
+                for mut entry in coverage {
+                    println!(
+                        "\n{} {}",
+                        tactic_label(entry.tactic, &format!("{}:", title_case_phase_name(entry.tactic))).bold(),
+                        format!("{} of {} uncovered", entry.missing.len(), entry.total).bright_cyan()
+                    );
+                    sort_objects(&mut entry.missing, SortOrder::Id);
+                    for technique in entry.missing {
+                        let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                        println!("  {} {}", format!("[{}]", mitre_id).bright_green(), technique.name.as_deref().unwrap_or("Unknown").bright_white());
                     }
                 }
+                return Ok(());
             }
             
-            if found_groups.is_empty() {
-                println!("{}", format!("No APT group found matching '{}'", name).red());
+            if print_structured_matches(found.as_slice(), ObjectFormat::resolve(cli.json, *format, default_format))? {
+                return Ok(());
+            }
+            
+            match found {
+                Some(obj) => print_mitigation_info(obj, &data, &options),
+                None => println!("{}", format!("No mitigation found with ID '{}'", id).red()),
+            }
+        },
+        
+        Commands::Gaps { covered_file, tactic } => {
+            let mut covered: HashSet<&str> = HashSet::new();
+            for id in read_id_list(covered_file)? {
+                match find_technique_by_id(&id, &data) {
+                    Some(technique) => {
+                        covered.insert(&technique.id);
+                    },
+                    None => eprintln!("{}", format!("Warning: ignoring unknown technique ID '{}'", id).yellow()),
+                }
+            }
+            
+            let coverage: Vec<TacticCoverage> = tactic_coverage(&covered, &data)
+                .into_iter()
+                .filter(|entry| tactic.as_ref().is_none_or(|name| normalize_tactic_name(entry.tactic) == normalize_tactic_name(name)))
+                .collect();
+            if coverage.is_empty() {
+                return Err(format!("No tactic found matching '{}'", tactic.as_deref().unwrap_or_default()).into());
+            }
+            
+            // A technique can sit under several tactics; count it once overall
+            let mut all: HashSet<&str> = HashSet::new();
+            let mut missing: HashSet<&str> = HashSet::new();
+            for entry in &coverage {
+                missing.extend(entry.missing.iter().map(|technique| technique.id.as_str()));
+            }
+            for technique in current_techniques(&data) {
+                if technique_tactics(technique).iter().any(|name| coverage.iter().any(|entry| entry.tactic == *name)) {
+                    all.insert(&technique.id);
+                }
+            }
+            
+            if cli.json {
+                let tactics: Vec<serde_json::Value> = coverage.iter()
+                    .map(|entry| serde_json::json!({
+                        "tactic": entry.tactic,
+                        "covered": entry.covered(),
+                        "total": entry.total,
+                        "missing": entry.missing.iter().filter_map(|technique| get_mitre_id(technique)).collect::<Vec<_>>(),
+                    }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "covered": all.len() - missing.len(),
+                    "total": all.len(),
+                    "tactics": tactics,
+                }))?);
+                return Ok(());
+            }
+            
+            println!("{}", "Coverage Gaps:".bright_cyan().bold());
+            print_separator();
+            for mut entry in coverage {
+                println!(
+                    "\n{} {}",
+                    tactic_label(entry.tactic, &format!("{}:", title_case_phase_name(entry.tactic))).bold(),
+                    format!("{}/{} covered", entry.covered(), entry.total).bright_cyan()
+                );
+                sort_objects(&mut entry.missing, SortOrder::Id);
+                for technique in entry.missing {
+                    let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                    println!("  {} {}", format!("[{}]", mitre_id).bright_green(), technique.name.as_deref().unwrap_or("Unknown").bright_white());
+                }
+            }
+            
+            let overall = all.len() - missing.len();
+            let percentage = overall as f64 * 100.0 / all.len().max(1) as f64;
+            println!("\n{}", format!("Overall: {}/{} techniques covered ({:.1}%)", overall, all.len(), percentage).bright_cyan().bold());
+        },
+        
+        Commands::TopTechniques { limit, tactic } => {
+            let tactic_obj = match tactic {
+                Some(name) => Some(find_tactic(name, &data).ok_or_else(|| format!("No tactic found matching '{}'", name))?),
+                None => None,
+            };
+            let ranking: Vec<TechniqueUsage> = rank_techniques_by_groups(tactic.as_deref(), &data)
+                .into_iter()
+                .take(*limit)
+                .collect();
+            
+            if cli.json {
+                let rows: Vec<serde_json::Value> = ranking.iter()
+                    .map(|entry| serde_json::json!({
+                        "id": get_mitre_id(entry.technique),
+                        "name": entry.technique.name,
+                        "groups": entry.groups,
+                    }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+                return Ok(());
+            }
+            
+            let heading = match tactic_obj {
+                Some(obj) => format!("Most used {} techniques:", obj.name.as_deref().unwrap_or("Unknown")),
+                None => "Most used techniques:".to_string(),
+            };
+            println!("{}", heading.bright_cyan().bold());
+            print_separator();
+            if ranking.is_empty() {
+                println!("{}", "No group is known to use any technique".yellow());
+            }
+            for entry in &ranking {
+                let mitre_id = get_mitre_id(entry.technique).unwrap_or_else(|| "N/A".to_string());
+                let noun = if entry.groups == 1 { "group" } else { "groups" };
+                println!(
+                    "{} {} — {}",
+                    format!("[{}]", mitre_id).bright_green(),
+                    entry.technique.name.as_deref().unwrap_or("Unknown").bright_white(),
+                    format!("used by {} {}", entry.groups, noun).bright_cyan()
+                );
+            }
+        },
+        
+        Commands::TopGroups { limit, tactic } => {
+            let tactic_obj = match tactic {
+                Some(name) => Some(find_tactic(name, &data).ok_or_else(|| format!("No tactic found matching '{}'", name))?),
+                None => None,
+            };
+            let ranking: Vec<GroupActivity> = rank_groups_by_technique_count(tactic.as_deref(), &data)
+                .into_iter()
+                .take(*limit)
+                .collect();
+            
+            if cli.json {
+                let rows: Vec<serde_json::Value> = ranking.iter()
+                    .map(|entry| serde_json::json!({
+                        "id": get_mitre_id(entry.group),
+                        "name": entry.group.name,
+                        "techniques": entry.techniques,
+                    }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+                return Ok(());
+            }
+            
+            let heading = match tactic_obj {
+                Some(obj) => format!("Groups with the most {} techniques:", obj.name.as_deref().unwrap_or("Unknown")),
+                None => "Groups with the most techniques:".to_string(),
+            };
+            println!("{}", heading.bright_cyan().bold());
+            print_separator();
+            if ranking.is_empty() {
+                println!("{}", "No group is known to use any technique".yellow());
+            }
+            for entry in &ranking {
+                let mitre_id = get_mitre_id(entry.group).unwrap_or_else(|| "N/A".to_string());
+                let noun = if entry.techniques == 1 { "technique" } else { "techniques" };
+                println!(
+                    "{} {} — {}",
+                    format!("[{}]", mitre_id).bright_green(),
+                    entry.group.name.as_deref().unwrap_or("Unknown").bright_white(),
+                    format!("{} {}", entry.techniques, noun).bright_cyan()
+                );
+            }
+        },
+        
+        Commands::Profile { group, format } => {
+            let options = DisplayOptions { profile: true, ..options.clone() };
+            let format = ProfileFormat::resolve(cli.json, *format, default_format);
+            let mut group = find_single_group(group, &data)?;
+            if !cli.no_follow_revoked {
+                group = resolve_revoked(group, &data);
+            }
+            
+            match format {
+                ProfileFormat::Json => {
+                    let (techniques, _) = group_technique_footprint(group, &data, &options);
+                    println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                        "group": group,
+                        "software": get_group_software(&group.id, &data),
+                        "campaigns": get_group_campaigns(&group.id, &data),
+                        "techniques": techniques,
+                    }))?);
+                },
+                ProfileFormat::Markdown => {
+                    colored::control::set_override(false);
+                    println!("{}", render_group_markdown(group, &data, &options).trim_end());
+                },
+                ProfileFormat::Text => print_group_info(group, &data, &options),
+            }
+        },
+        
+        Commands::Attribute { techniques, techniques_file, limit } => {
+            let mut requested = techniques.clone();
+            if let Some(path) = techniques_file {
+                requested.extend(read_id_list(path)?);
+            }
+            if requested.is_empty() {
+                return Err("Give technique IDs as arguments or with --techniques-file".into());
+            }
+            
+            let mut observed: Vec<&str> = Vec::new();
+            for id in &requested {
+                match find_technique_by_id(id, &data) {
+                    Some(technique) => observed.push(&technique.id),
+                    None => eprintln!("{}", format!("Warning: no technique with ID '{}'", id).yellow()),
+                }
+            }
+            observed.sort();
+            observed.dedup();
+            
+            let ranking: Vec<GroupMatch> = rank_groups_by_techniques(&observed, &data)
+                .into_iter()
+                .filter(|entry| entry.group.revoked != Some(true))
+                .filter(|entry| cli.include_deprecated || !entry.group.is_deprecated())
+                .take(*limit)
+                .collect();
+            
+            if cli.json {
+                let rows: Vec<serde_json::Value> = ranking.iter()
+                    .map(|entry| serde_json::json!({
+                        "id": get_mitre_id(entry.group),
+                        "name": entry.group.name,
+                        "matched": entry.matched,
+                        "observed": observed.len(),
+                        "known_techniques": entry.known,
+                    }))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+                return Ok(());
+            }
+            
+            println!("{}", format!("Groups using the {} observed techniques:", observed.len()).bright_cyan().bold());
+            print_separator();
+            if ranking.is_empty() {
+                println!("{}", "No group is known to use any of these techniques".yellow());
+            }
+            for entry in &ranking {
+                let mitre_id = get_mitre_id(entry.group).unwrap_or_else(|| "N/A".to_string());
+                let percentage = entry.matched as f64 * 100.0 / observed.len() as f64;
+                println!(
+                    "{} {} — {} {}",
+                    format!("[{}]", mitre_id).bright_green(),
+                    entry.group.name.as_deref().unwrap_or("Unknown").bright_white(),
+                    format!("{}/{} ({:.1}%)", entry.matched, observed.len(), percentage).bright_cyan(),
+                    format!("of {} known techniques", entry.known).bright_black()
+                );
+            }
+        },
+        
+        Commands::Compare { group_a, group_b } => {
+            let resolve = |query: &str| find_group(query, &data).ok_or_else(|| format!("No APT group found matching '{}'", query));
+            let (first, second) = (resolve(group_a)?, resolve(group_b)?);
+            let overlap = compare_group_techniques(&first.id, &second.id, &data);
+            let label = |group: &AttackObject| {
+                format!("{} ({})", group.name.as_deref().unwrap_or("Unknown"), get_mitre_id(group).unwrap_or_else(|| "N/A".to_string()))
+            };
+            
+            if cli.json {
+                let ids = |techniques: &[&AttackObject]| -> Vec<String> {
+                    let mut ids: Vec<String> = techniques.iter().filter_map(|technique| get_mitre_id(technique)).collect();
+                    ids.sort_by(|a, b| compare_mitre_ids(a, b));
+                    ids
+                };
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "groups": [get_mitre_id(first), get_mitre_id(second)],
+                    "jaccard": overlap.jaccard(),
+                    "shared": ids(&overlap.shared),
+                    "only_first": ids(&overlap.only_first),
+                    "only_second": ids(&overlap.only_second),
+                }))?);
+                return Ok(());
+            }
+            
+            println!("{}", format!("{} vs {}", label(first), label(second)).bright_cyan().bold());
+            print_separator();
+            
+            if overlap.shared.is_empty() {
+                println!("{}", "No techniques in common".yellow());
             } else {
-                for (i, obj) in found_groups.iter().enumerate() {
-                    if i > 0 {
-                        print_separator();
+                println!("{}", "Shared Techniques:".bright_white().bold());
+                for (tactic, mut techniques) in group_techniques_by_tactic(&overlap.shared) {
+                    println!("\n  {}", tactic_label(&tactic, &format!("{}:", tactic)).bold());
+                    sort_objects(&mut techniques, SortOrder::Name);
+                    for technique in techniques {
+                        let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                        let tech_name = technique.name.as_deref().unwrap_or("Unknown");
+                        println!("    {} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
                     }
-                    print_group_info(obj, &data);
                 }
             }
+            
+            println!();
+            println!("{}", format!("Shared techniques: {}", overlap.shared.len()).bright_cyan());
+            println!("{}", format!("Only {}: {}", label(first), overlap.only_first.len()).bright_white());
+            println!("{}", format!("Only {}: {}", label(second), overlap.only_second.len()).bright_white());
+            println!("{}", format!("Jaccard similarity: {:.3}", overlap.jaccard()).bright_cyan().bold());
         },
         
-        Commands::TechniqueId { id } => {
-            let id_upper = id.to_uppercase();
-            let mut found = false;
+        Commands::DataSource { name } => {
+            let techniques = find_techniques_by_data_source(name, &data);
             
-            for obj in &data.objects {
-                if obj.obj_type == "attack-pattern" {
-                    if let Some(mitre_id) = get_mitre_id(obj) {
-                        if mitre_id == id_upper {
-                            print_technique_info(obj, &data);
-                            found = true;
-                            break;
-                        }
-                    }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&techniques)?);
+                if techniques.is_empty() {
+                    return Err(QuietFailure.into());
+                }
+                return Ok(());
+            }
+            
+            if techniques.is_empty() {
+                println!("{}", format!("No techniques list '{}' as a data source", name).red());
+                return Ok(());
+            }
+            
+            println!("{}", format!("Techniques observable through '{}':", name).bright_cyan().bold());
+            print_separator();
+            
+            for (tactic, mut tactic_techniques) in group_techniques_by_tactic(&techniques) {
+                println!("\n  {}", tactic_label(&tactic, &format!("{}:", tactic)).bold());
+                sort_objects(&mut tactic_techniques, SortOrder::Id);
+                
+                for technique in tactic_techniques {
+                    let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                    let tech_name = technique.name.as_deref().unwrap_or("Unknown");
+                    println!("    {} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
                 }
             }
             
-            if !found {
-                println!("{}", format!("No technique found with ID '{}'", id).red());
+            let total = current_techniques(&data).count();
+            let percentage = techniques.len() as f64 * 100.0 / total.max(1) as f64;
+            println!(
+                "\n{}",
+                format!("Coverage: {} of {} techniques ({:.1}%)", techniques.len(), total, percentage).bright_cyan()
+            );
+        },
+        
+        Commands::Open { id } => {
+            let Some(obj) = find_object(id, &data) else {
+                return Err(format!("No object found with ID '{}'", id).into());
+            };
+            let Some(url) = attack_url(obj) else {
+                return Err(format!("'{}' has no page on attack.mitre.org", id).into());
+            };
+            
+            // Print first so the link is still usable where no browser can be launched
+            println!("{}", url);
+            if let Err(err) = open_in_browser(&url) {
+                eprintln!("{}", format!("Could not open a browser: {}", err).yellow());
             }
         },
         
-        Commands::TechniqueName { name } => {
-            let name_lower = name.to_lowercase();
-            let mut found = false;
+        Commands::Which { id } => {
+            let Some(obj) = find_object(id, &data) else {
+                eprintln!("{}", format!("unknown id '{}'", id).red());
+                return Err(QuietFailure.into());
+            };
             
-            for obj in &data.objects {
-                if obj.obj_type == "attack-pattern" {
-                    if let Some(obj_name) = &obj.name {
-                        if obj_name.to_lowercase().contains(&name_lower) {
-                            if found {
-                                print_separator();
-                            }
-                            print_technique_info(obj, &data);
-                            found = true;
-                        }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                    "type": object_type_name(obj),
+                    "id": get_mitre_id(obj),
+                    "stix_id": obj.id,
+                    "name": obj.name,
+                }))?);
+                return Ok(());
+            }
+            
+            // Tab-separated so scripts can `cut -f1` the type
+            println!("{}\t{}", object_type_name(obj).bright_yellow(), obj.name.as_deref().unwrap_or("Unknown").bright_white());
+        },
+        
+        Commands::Relationships { id } => {
+            let Some(obj) = find_object(id, &data) else {
+                return Err(format!("No object found with ID '{}'", id).into());
+            };
+            let relationships = get_relationships(&obj.id, &data);
+            
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&relationships)?);
+                return Ok(());
+            }
+            
+            print_relationships(obj, &relationships, &data);
+        },
+        
+        Commands::MitigationList { format, sort, limit, wrap_width, count_only } => {
+            let coverage = get_mitigation_coverage(&data);
+            
+            let mut mitigations: Vec<MitigationSummary> = data.objects
+                .iter()
+                .filter(|obj| obj.obj_type == "course-of-action")
+                .filter(|obj| cli.include_deprecated || !obj.is_deprecated())
+                .map(|obj| MitigationSummary {
+                    id: get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string()),
+                    name: obj.name.clone().unwrap_or_else(|| "Unknown".to_string()),
+                    techniques: coverage.get(obj.id.as_str()).copied().unwrap_or(0),
+                })
+                .collect();
+            if *count_only {
+                return print_count(mitigations.len());
+            }
+            
+            match sort {
+                MitigationSort::Id => mitigations.sort_by(|a, b| compare_mitre_ids(&a.id, &b.id)),
+                MitigationSort::Name => mitigations.sort_by(|a, b| a.name.cmp(&b.name)),
+                MitigationSort::Coverage => mitigations.sort_by(|a, b| {
+                    b.techniques.cmp(&a.techniques).then_with(|| compare_mitre_ids(&a.id, &b.id))
+                }),
+            }
+            let hidden = apply_limit(&mut mitigations, *limit);
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&mitigations)?);
+                return Ok(());
+            }
+            
+            let format = if cli.json { OutputFormat::Json } else { format.unwrap_or(default_format) };
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&mitigations)?),
+                OutputFormat::Yaml => print!("{}", to_yaml(&serde_json::to_value(&mitigations)?)),
+                OutputFormat::Table => {
+                    let rows: Vec<Vec<String>> = mitigations.iter()
+                        .map(|mitigation| vec![
+                            mitigation.id.clone(),
+                            mitigation.name.clone(),
+                            mitigation.techniques.to_string(),
+                        ])
+                        .collect();
+                    print_table(
+                        &["ID", "Name", "Techniques"],
+                        &rows,
+                        &[false, true, false],
+                        wrap_width.unwrap_or_else(get_terminal_width),
+                    );
+                    print_limit_notice(hidden);
+                }
+                OutputFormat::Markdown => {
+                    let rows: Vec<Vec<String>> = mitigations.iter()
+                        .map(|mitigation| vec![
+                            mitigation.id.clone(),
+                            mitigation.name.clone(),
+                            mitigation.techniques.to_string(),
+                        ])
+                        .collect();
+                    print!("{}", markdown_table(&["ID", "Name", "Techniques"], &rows));
+                }
+                OutputFormat::Text => {
+                    println!("{}", "Mitigations:".bright_cyan().bold());
+                    print_separator();
+                    
+                    for mitigation in &mitigations {
+                        println!(
+                            "{} {} {}",
+                            format!("[{}]", mitigation.id).bright_green(),
+                            mitigation.name.bright_white(),
+                            format!("({} techniques)", mitigation.techniques).bright_black()
+                        );
                     }
+                    print_limit_notice(hidden);
+                }
+            }
+        },
+        
+        Commands::SearchDetection { query } => {
+            let mut found = search_detections(query, SNIPPET_CONTEXT, &data);
+            if !cli.include_deprecated {
+                found.retain(|(technique, _)| !technique.is_deprecated());
+            }
+            found.sort_by(|(a, _), (b, _)| compare_mitre_ids(&get_mitre_id(a).unwrap_or_default(), &get_mitre_id(b).unwrap_or_default()));
+            
+            if cli.json {
+                let techniques: Vec<&AttackObject> = found.iter().map(|(technique, _)| *technique).collect();
+                println!("{}", serde_json::to_string_pretty(&techniques)?);
+                if techniques.is_empty() {
+                    return Err(QuietFailure.into());
                 }
+                return Ok(());
+            }
+            
+            if found.is_empty() {
+                println!("{}", format!("No detection guidance mentions '{}'", query).red());
+                return Ok(());
             }
             
-            if !found {
-                println!("{}", format!("No technique found matching '{}'", name).red());
+            for (technique, snippet) in &found {
+                let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
+                println!("{} {}", format!("[{}]", mitre_id).bright_green(), technique.name.as_deref().unwrap_or("Unknown").bright_white());
+                print_snippet(snippet);
             }
+            println!("\n{}", format!("{} techniques match '{}'", found.len(), query).bright_cyan());
         },
         
-        Commands::Tactic { name } => {
-            let name_lower = name.to_lowercase().replace("-", "_").replace(" ", "_");
-            let mut found = false;
+        Commands::Search { query, any, kind, limit, count_only } => {
+            let mut matches = search_terms(query, *any, *kind, &data);
+            if !cli.include_deprecated {
+                matches.retain(|entry| !entry.object.is_deprecated());
+            }
+            if *count_only {
+                return print_count(matches.len());
+            }
+            matches.sort_by(|a, b| {
+                ObjectKind::of(a.object).cmp(&ObjectKind::of(b.object)).then_with(|| a.object.name.cmp(&b.object.name))
+            });
+            let total = matches.len();
+            let hidden = apply_limit(&mut matches, *limit);
+            let found: Vec<&AttackObject> = matches.iter().map(|entry| entry.object).collect();
             
-            // First look for x-mitre-tactic objects
-            for obj in &data.objects {
-                if obj.obj_type == "x-mitre-tactic" {
+            if cli.json_lines {
+                print!("{}", json_lines(&found)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&found)?);
+                if found.is_empty() {
+                    return Err(QuietFailure.into());
+                }
+                return Ok(());
+            }
+            
+            if found.is_empty() {
+                println!("{}", format!("Nothing found matching '{}'", query).red());
+                return Ok(());
+            }
+            
+            let mut by_kind: HashMap<ObjectKind, Vec<&SearchMatch>> = HashMap::new();
+            for entry in &matches {
+                if let Some(obj_kind) = ObjectKind::of(entry.object) {
+                    by_kind.entry(obj_kind).or_default().push(entry);
+                }
+            }
+            
+            let mut sorted_kinds: Vec<_> = by_kind.into_iter().collect();
+            sorted_kinds.sort_by_key(|(obj_kind, _)| *obj_kind);
+            let several_terms = query.split_whitespace().nth(1).is_some();
+            
+            for (obj_kind, entries) in sorted_kinds {
+                println!("\n{}", format!("{}:", obj_kind.heading()).bright_white().bold());
+                
+                for entry in entries {
+                    let obj = entry.object;
                     if let Some(obj_name) = &obj.name {
-                        if obj_name.to_lowercase().replace("-", "_").replace(" ", "_").contains(&name_lower) {
-                            if found {
-                                print_separator();
-                            }
-                            print_tactic_info(obj);
-                            found = true;
+                        let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+                        let name_term = entry.terms.iter().find(|(_, field)| *field == MatchField::Name).map(|(term, _)| term.as_str());
+                        println!("  {} {}", format!("[{}]", mitre_id).bright_green(), highlight_match(obj_name, name_term, |text| text.bright_white()));
+                        // Matched only in the description: show where
+                        if entry.terms.iter().all(|(_, field)| *field == MatchField::Description)
+                            && let Some((term, _)) = entry.terms.first()
+                            && let Some(snippet) = obj.description.as_deref().and_then(|desc| find_snippet(desc, term, SNIPPET_CONTEXT)) {
+                            print!("  ");
+                            print_snippet(&snippet);
                         }
-                    }
-                    
-                    if let Some(shortname) = &obj.shortname {
-                        if shortname.to_lowercase().replace("-", "_").contains(&name_lower) {
-                            if found {
-                                print_separator();
-                            }
-                            print_tactic_info(obj);
-                            found = true;
+                        if several_terms {
+                            let how: Vec<String> = entry.terms.iter().map(|(term, field)| format!("{} ({})", term, field.label())).collect();
+                            println!("    {}", format!("matched: {}", how.join(", ")).bright_black());
                         }
                     }
                 }
             }
             
-            // If we found a tactic, also show related techniques
-            if found {
-                println!("\n{}", "Related Techniques:".bright_white().bold());
-                print_separator();
-                
-                let mut techniques: Vec<&AttackObject> = data.objects
+            println!();
+            print_limit_notice(hidden);
+            println!("{}", format!("Total Matches: {}", total).bright_cyan());
+        },
+        
+        Commands::Recent { since } => {
+            let since = since.clone().unwrap_or_else(|| days_ago(RECENT_DEFAULT_DAYS));
+            let mut found = modified_since(&since, &data);
+            if !cli.include_deprecated {
+                found.retain(|obj| !obj.is_deprecated());
+            }
+            
+            if cli.json_lines {
+                print!("{}", json_lines(&found)?);
+                return Ok(());
+            }
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&found)?);
+                return Ok(());
+            }
+            
+            if found.is_empty() {
+                println!("{}", format!("Nothing modified since {}", since).red());
+                return Ok(());
+            }
+            
+            let mut current_kind = None;
+            for obj in &found {
+                let obj_kind = ObjectKind::of(obj);
+                if obj_kind != current_kind {
+                    if let Some(obj_kind) = obj_kind {
+                        println!("\n{}", format!("{}:", obj_kind.heading()).bright_white().bold());
+                    }
+                    current_kind = obj_kind;
+                }
+                let mitre_id = get_mitre_id(obj).unwrap_or_else(|| "N/A".to_string());
+                let modified = obj.modified.as_deref().unwrap_or_default();
+                println!(
+                    "  {} {} {}",
+                    modified.get(..10).unwrap_or(modified).bright_black(),
+                    format!("[{}]", mitre_id).bright_green(),
+                    obj.name.as_deref().unwrap_or("Unknown").bright_white()
+                );
+            }
+            println!("\n{}", format!("{} objects modified since {}", found.len(), since).bright_cyan());
+        },
+        
+        Commands::NavigatorLayer { group } => {
+            let group_obj = find_single_group(group, &data)?;
+            let layer = build_navigator_layer(group_obj, &data, domain);
+            write_output(&serde_json::to_string_pretty(&layer)?, cli.output.as_deref())?;
+        },
+        
+        Commands::Export { group_technique_matrix: true, groups, max_columns, .. } => {
+            let mut columns: Vec<&AttackObject> = if groups.is_empty() {
+                let mut all: Vec<&AttackObject> = data.objects
                     .iter()
-                    .filter(|obj| {
-                        if obj.obj_type == "attack-pattern" {
-                            if let Some(phases) = &obj.kill_chain_phases {
-                                return phases.iter().any(|phase| {
-                                    phase.kill_chain_name == "mitre-attack" && 
-                                    phase.phase_name.to_lowercase().replace("-", "_").contains(&name_lower)
-                                });
-                            }
-                        }
-                        false
-                    })
+                    .filter(|obj| obj.obj_type == "intrusion-set" && !obj.is_sunset())
                     .collect();
-                
-                techniques.sort_by(|a, b| {
-                    a.name.as_ref().unwrap_or(&"".to_string())
-                        .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
-                });
-                
-                for technique in techniques {
-                    if let Some(tech_name) = &technique.name {
-                        let mitre_id = get_mitre_id(technique).unwrap_or_else(|| "N/A".to_string());
-                        println!("{} {}", format!("[{}]", mitre_id).bright_green(), tech_name.bright_white());
+                all.sort_by(|a, b| a.name.cmp(&b.name));
+                all
+            } else {
+                let mut selected = Vec::new();
+                for query in groups {
+                    match find_group(query, &data) {
+                        Some(group) => selected.push(group),
+                        None => return Err(format!("No APT group found matching '{}'", query).into()),
                     }
                 }
+                selected
+            };
+            
+            if columns.len() > *max_columns {
+                eprintln!("{}", format!(
+                    "Warning: {} groups selected, keeping the first {} columns (raise with --max-columns)",
+                    columns.len(), max_columns
+                ).yellow());
+                columns.truncate(*max_columns);
             }
             
-            if !found {
-                println!("{}", format!("No tactic found matching '{}'", name).red());
-                println!("\n{}", "Available tactics:".bright_white().bold());
-                
-                let mut tactics: Vec<&AttackObject> = data.objects
-                    .iter()
-                    .filter(|obj| obj.obj_type == "x-mitre-tactic")
-                    .collect();
-                
-                tactics.sort_by(|a, b| {
-                    a.name.as_ref().unwrap_or(&"".to_string())
-                        .cmp(b.name.as_ref().unwrap_or(&"".to_string()))
-                });
-                
-                for tactic in tactics {
-                    if let Some(tactic_name) = &tactic.name {
-                        let shortname = tactic.shortname.as_ref()
-                            .map(|s| format!(" ({})", s))
-                            .unwrap_or_default();
-                        println!("  • {}{}", tactic_name.bright_cyan(), shortname.bright_black());
+            write_output(&build_group_technique_matrix(&columns, &data), cli.output.as_deref())?;
+        },
+        
+        Commands::Export { since_diff, .. } => {
+            let objects = load_raw_objects(&matrix_path)?;
+            
+            match since_diff {
+                Some(old_path) => {
+                    let old_objects = load_raw_objects(old_path)?;
+                    let diff = diff_bundles(&old_objects, &objects);
+                    let delta: Vec<&serde_json::Value> = diff.added.iter()
+                        .chain(diff.changed.iter())
+                        .copied()
+                        .collect();
+                    
+                    write_bundle(&delta, cli.output.as_deref())?;
+                    eprintln!("{}", format!(
+                        "Exported {} objects ({} added, {} changed)",
+                        delta.len(), diff.added.len(), diff.changed.len()
+                    ).bright_green());
+                    if !diff.removed.is_empty() {
+                        eprintln!("{}", format!(
+                            "{} objects were removed; removals cannot be expressed in a bundle and were skipped",
+                            diff.removed.len()
+                        ).yellow());
                     }
                 }
+                None => {
+                    let all: Vec<&serde_json::Value> = objects.iter().collect();
+                    write_bundle(&all, cli.output.as_deref())?;
+                }
+            }
+        },
+        
+        Commands::ExportCsv { out } => {
+            let content = if cli.json_lines {
+                json_lines(&exported_techniques(&data))?
+            } else {
+                build_technique_csv(&data)
+            };
+            write_output(&content, out.as_deref().or(cli.output.as_deref()))?;
+        },
+        
+        Commands::ExportDot { group, .. } => {
+            let groups: Vec<&AttackObject> = match group {
+                Some(group) => vec![find_single_group(group, &data)?],
+                None => {
+                    let mut all: Vec<&AttackObject> = data.objects
+                        .iter()
+                        .filter(|obj| obj.obj_type == "intrusion-set" && obj.revoked != Some(true))
+                        .filter(|obj| cli.include_deprecated || !obj.is_deprecated())
+                        .collect();
+                    sort_objects(&mut all, SortOrder::Name);
+                    all
+                },
+            };
+            write_output(&build_dot_graph(&groups, &data), cli.output.as_deref())?;
+        },
+        
+        Commands::Benchmark { lookups } => {
+            let report = run_benchmark(&data, load_time, *lookups);
+            
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_benchmark_report(&report);
+            }
+        },
+        
+        Commands::Validate => {
+            let issues = validate_bundle(&data);
+            for issue in &issues {
+                let mitre_id = get_mitre_id(issue.object).map(|id| format!(" ({})", id)).unwrap_or_default();
+                println!("{}{} {}", issue.object.id.bright_yellow(), mitre_id.bright_green(), issue.problem);
+            }
+            
+            if issues.is_empty() {
+                println!("{}", format!("No problems found in {} objects", data.objects.len()).bright_green());
+            } else {
+                println!("\n{}", format!("{} problems found in {} objects", issues.len(), data.objects.len()).red());
+                return Err(QuietFailure.into());
+            }
+        },
+        
+        Commands::Version => {
+            let attack = attack_version(&data);
+            let modified = last_modified(&data);
+            if cli.json {
+                let json = serde_json::json!({
+                    "attcli": env!("CARGO_PKG_VERSION"),
+                    "attack_version": attack,
+                    "last_modified": modified,
+                    "matrix": matrix_path,
+                });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+                return Ok(());
             }
+            
+            println!("{} {}", "attcli".bright_cyan().bold(), env!("CARGO_PKG_VERSION"));
+            println!("{} {}", "ATT&CK version:".bright_white().bold(), attack.unwrap_or("unknown"));
+            println!("{} {}", "Last modified:".bright_white().bold(), modified.unwrap_or("unknown"));
+            println!("{} {}", "Matrix:".bright_white().bold(), matrix_path.display().to_string().bright_black());
         },
+        
+        Commands::Completions { .. } | Commands::ConfigPath | Commands::CacheClear | Commands::Update { .. } | Commands::Diff { .. } => unreachable!("handled before loading the matrix"),
     }
     
     Ok(())
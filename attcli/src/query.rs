@@ -0,0 +1,1319 @@
+use clap::ValueEnum;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::{get_mitre_id, AttackData, AttackObject};
+
+/// The object kinds users browse, in the order search results are shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum)]
+pub enum ObjectKind {
+    Technique,
+    Group,
+    Software,
+    Mitigation,
+    Tactic,
+}
+
+impl ObjectKind {
+    pub fn of(obj: &AttackObject) -> Option<Self> {
+        match obj.obj_type.as_str() {
+            "attack-pattern" => Some(ObjectKind::Technique),
+            "intrusion-set" => Some(ObjectKind::Group),
+            "malware" | "tool" => Some(ObjectKind::Software),
+            "course-of-action" => Some(ObjectKind::Mitigation),
+            "x-mitre-tactic" => Some(ObjectKind::Tactic),
+            _ => None,
+        }
+    }
+    
+    pub fn heading(self) -> &'static str {
+        match self {
+            ObjectKind::Technique => "Techniques",
+            ObjectKind::Group => "Groups",
+            ObjectKind::Software => "Software",
+            ObjectKind::Mitigation => "Mitigations",
+            ObjectKind::Tactic => "Tactics",
+        }
+    }
+}
+
+/// How listings order their objects
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortOrder {
+    /// Alphabetically by name
+    #[default]
+    Name,
+    /// By MITRE ID, sub-techniques right after their parent
+    Id,
+}
+
+/// How several `--platform` values combine
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum PlatformMatch {
+    /// Runs on at least one of them
+    #[default]
+    Any,
+    /// Runs on every one of them
+    All,
+}
+
+/// Which techniques a group's technique total counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TechniqueCount {
+    /// Techniques and sub-techniques alike
+    #[default]
+    All,
+    /// Only top-level techniques
+    Parents,
+    /// Only sub-techniques
+    Subs,
+}
+
+impl TechniqueCount {
+    pub fn includes(self, technique: &AttackObject) -> bool {
+        let is_sub = technique.is_subtechnique == Some(true);
+        match self {
+            TechniqueCount::All => true,
+            TechniqueCount::Parents => !is_sub,
+            TechniqueCount::Subs => is_sub,
+        }
+    }
+}
+
+/// How technique listings order their tactic sections
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TacticOrder {
+    /// Alphabetically by tactic name
+    Alpha,
+    /// In the order an intrusion progresses, as the matrix lists its tactics
+    #[default]
+    Killchain,
+}
+
+// Splits "T1055.012" into ("T", 1055, Some(12)); anything unparseable sorts as plain text after
+fn mitre_id_key(id: &str) -> Option<(&str, u64, Option<u64>)> {
+    let digits_start = id.find(|c: char| c.is_ascii_digit())?;
+    let (prefix, rest) = id.split_at(digits_start);
+    let (number, sub) = match rest.split_once('.') {
+        Some((number, sub)) => (number, Some(sub.parse().ok()?)),
+        None => (rest, None),
+    };
+    Some((prefix, number.parse().ok()?, sub))
+}
+
+/// Orders MITRE IDs numerically: T1055 < T1055.001 < T1059, and T999 < T1000
+pub fn compare_mitre_ids(a: &str, b: &str) -> Ordering {
+    match (mitre_id_key(a), mitre_id_key(b)) {
+        (Some(a_key), Some(b_key)) => a_key.cmp(&b_key),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Sorts by name or by MITRE ID; objects without an ID go last, and ties fall back to name
+pub fn sort_objects(objects: &mut [&AttackObject], order: SortOrder) {
+    objects.sort_by(|a, b| {
+        let by_name = a.name.as_deref().unwrap_or_default().cmp(b.name.as_deref().unwrap_or_default());
+        match order {
+            SortOrder::Name => by_name,
+            SortOrder::Id => match (get_mitre_id(a), get_mitre_id(b)) {
+                (Some(a_id), Some(b_id)) => compare_mitre_ids(&a_id, &b_id),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }.then(by_name),
+        }
+    });
+}
+
+pub fn get_related_techniques<'a>(group_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    dedupe_by_id(data.related_targets(group_id, "uses")
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .collect())
+}
+
+/// A technique in a group's footprint, used either directly or through the group's software
+#[derive(Debug)]
+pub struct GroupTechnique<'a> {
+    pub technique: &'a AttackObject,
+    /// Malware and tools that bring the technique in; empty when the group uses it directly
+    pub via: Vec<&'a AttackObject>,
+}
+
+/// The group's techniques plus those of the software it uses (`group --uses--> software --uses--> technique`).
+/// Direct techniques come first and are never repeated as transitive ones.
+pub fn get_all_group_techniques<'a>(group_id: &str, data: &'a AttackData) -> Vec<GroupTechnique<'a>> {
+    let mut techniques: Vec<GroupTechnique> = get_related_techniques(group_id, data)
+        .into_iter()
+        .map(|technique| GroupTechnique { technique, via: Vec::new() })
+        .collect();
+    let direct: HashSet<&str> = techniques.iter().map(|entry| entry.technique.id.as_str()).collect();
+    
+    let mut transitive: HashMap<&str, usize> = HashMap::new();
+    for tool in get_group_software(group_id, data) {
+        for technique in get_related_techniques(&tool.id, data) {
+            if direct.contains(technique.id.as_str()) {
+                continue;
+            }
+            match transitive.get(technique.id.as_str()) {
+                Some(&i) => techniques[i].via.push(tool),
+                None => {
+                    transitive.insert(&technique.id, techniques.len());
+                    techniques.push(GroupTechnique { technique, via: vec![tool] });
+                },
+            }
+        }
+    }
+    techniques
+}
+
+/// The malware and tools a group uses, sorted by name
+pub fn get_group_software<'a>(group_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let mut software = dedupe_by_id(data.related_targets(group_id, "uses").filter(|obj| is_software(obj)).collect());
+    software.sort_by(|a, b| a.name.cmp(&b.name));
+    software
+}
+
+/// The campaigns attributed to a group, oldest first (by `first_seen`, then name)
+pub fn get_group_campaigns<'a>(group_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let mut campaigns = dedupe_by_id(data.related_sources(group_id, "attributed-to")
+        .filter(|obj| obj.obj_type == "campaign")
+        .collect());
+    campaigns.sort_by(|a, b| a.first_seen.cmp(&b.first_seen).then_with(|| a.name.cmp(&b.name)));
+    campaigns
+}
+
+pub fn get_mitigated_techniques<'a>(mitigation_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    dedupe_by_id(data.related_targets(mitigation_id, "mitigates")
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .collect())
+}
+
+/// Sub-techniques point at their parent through `subtechnique-of` relationships
+pub fn get_subtechniques<'a>(parent_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    dedupe_by_id(data.related_sources(parent_id, "subtechnique-of")
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .collect())
+}
+
+pub fn get_parent_technique<'a>(child_id: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    data.related_targets(child_id, "subtechnique-of")
+        .find(|obj| obj.obj_type == "attack-pattern")
+}
+
+/// A technique and the sub-techniques listed under it
+#[derive(Debug)]
+pub struct TechniqueTree<'a> {
+    pub technique: &'a AttackObject,
+    /// Sorted by MITRE ID
+    pub subtechniques: Vec<&'a AttackObject>,
+}
+
+/// Nests the sub-techniques among `techniques` under their parent, keeping the order of the rest.
+/// A sub-technique whose parent is not among them stays at the top level.
+pub fn nest_subtechniques<'a>(techniques: &[&'a AttackObject], data: &'a AttackData) -> Vec<TechniqueTree<'a>> {
+    let parents: HashSet<&str> = techniques.iter()
+        .filter(|technique| technique.is_subtechnique != Some(true))
+        .map(|technique| technique.id.as_str())
+        .collect();
+    
+    let mut trees: Vec<TechniqueTree> = Vec::new();
+    let mut nested: Vec<(&str, &AttackObject)> = Vec::new();
+    for &technique in techniques {
+        let parent = (technique.is_subtechnique == Some(true))
+            .then(|| get_parent_technique(&technique.id, data))
+            .flatten()
+            .filter(|parent| parents.contains(parent.id.as_str()));
+        match parent {
+            Some(parent) => nested.push((&parent.id, technique)),
+            None => trees.push(TechniqueTree { technique, subtechniques: Vec::new() }),
+        }
+    }
+    for (parent_id, technique) in nested {
+        if let Some(tree) = trees.iter_mut().find(|tree| tree.technique.id == parent_id) {
+            tree.subtechniques.push(technique);
+        }
+    }
+    for tree in &mut trees {
+        sort_objects(&mut tree.subtechniques, SortOrder::Id);
+    }
+    trees
+}
+
+/// Groups that use this technique or software, each paired with the first `uses` relationship
+/// linking them (its description is the procedure example)
+pub fn get_related_groups<'a>(target_id: &str, data: &'a AttackData) -> Vec<(&'a AttackObject, &'a AttackObject)> {
+    let mut seen = HashSet::new();
+    data.related_source_links(target_id, "uses")
+        .filter(|(_, obj)| obj.obj_type == "intrusion-set")
+        .filter(|(_, obj)| seen.insert(obj.id.as_str()))
+        .map(|(relationship, group)| (group, relationship))
+        .collect()
+}
+
+/// Mitigations for this technique, each paired with the first `mitigates` relationship linking
+/// them (its description is the technique-specific guidance)
+pub fn get_mitigations_for_technique<'a>(technique_id: &str, data: &'a AttackData) -> Vec<(&'a AttackObject, &'a AttackObject)> {
+    let mut seen = HashSet::new();
+    data.related_source_links(technique_id, "mitigates")
+        .filter(|(_, obj)| obj.obj_type == "course-of-action")
+        .filter(|(_, obj)| seen.insert(obj.id.as_str()))
+        .map(|(relationship, mitigation)| (mitigation, relationship))
+        .collect()
+}
+
+/// Data components that detect this technique, each with the data source it belongs to
+pub fn get_detecting_components<'a>(technique_id: &str, data: &'a AttackData) -> Vec<(&'a AttackObject, Option<&'a AttackObject>)> {
+    let mut seen = HashSet::new();
+    data.related_sources(technique_id, "detects")
+        .filter(|obj| obj.obj_type == "x-mitre-data-component" && obj.revoked != Some(true))
+        .filter(|obj| seen.insert(obj.id.as_str()))
+        .map(|component| (component, component.data_source_ref.as_deref().and_then(|source_ref| data.get(source_ref))))
+        .collect()
+}
+
+/// Where a group is believed to operate from, when the bundle says so in a structured way: its own
+/// `x_mitre_country`/`x_mitre_region`, the `location`s it `originates-from`, and external references
+/// named "country". Case-insensitive duplicates are dropped.
+pub fn group_regions<'a>(group: &'a AttackObject, data: &'a AttackData) -> Vec<&'a str> {
+    let mut regions: Vec<&str> = group.country.iter().chain(&group.region).map(String::as_str).collect();
+    regions.extend(data.related_targets(&group.id, "originates-from")
+        .filter(|obj| obj.obj_type == "location")
+        .filter_map(|location| location.name.as_deref().or(location.country.as_deref()).or(location.region.as_deref())));
+    regions.extend(group.external_references.iter().flatten()
+        .filter(|reference| reference.source_name.eq_ignore_ascii_case("country"))
+        .filter_map(|reference| reference.description.as_deref().or(reference.external_id.as_deref())));
+    
+    let mut seen = HashSet::new();
+    regions.retain(|region| seen.insert(region.to_lowercase()));
+    regions
+}
+
+/// Techniques linked to this one by `related-to` relationships in either direction, by MITRE ID
+pub fn get_linked_techniques<'a>(technique_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let mut linked = dedupe_by_id(data.related_targets(technique_id, "related-to")
+        .chain(data.related_sources(technique_id, "related-to"))
+        .filter(|obj| obj.obj_type == "attack-pattern" && obj.revoked != Some(true) && obj.id != technique_id)
+        .collect());
+    sort_objects(&mut linked, SortOrder::Id);
+    linked
+}
+
+/// How two groups' technique sets overlap
+#[derive(Debug)]
+pub struct TechniqueOverlap<'a> {
+    pub shared: Vec<&'a AttackObject>,
+    pub only_first: Vec<&'a AttackObject>,
+    pub only_second: Vec<&'a AttackObject>,
+}
+
+impl TechniqueOverlap<'_> {
+    /// Shared techniques over all techniques either group uses (0.0 when neither uses any)
+    pub fn jaccard(&self) -> f64 {
+        let union = self.shared.len() + self.only_first.len() + self.only_second.len();
+        if union == 0 {
+            return 0.0;
+        }
+        self.shared.len() as f64 / union as f64
+    }
+}
+
+pub fn compare_group_techniques<'a>(first_id: &str, second_id: &str, data: &'a AttackData) -> TechniqueOverlap<'a> {
+    let first = get_related_techniques(first_id, data);
+    let second = get_related_techniques(second_id, data);
+    let first_ids: HashSet<&str> = first.iter().map(|obj| obj.id.as_str()).collect();
+    let second_ids: HashSet<&str> = second.iter().map(|obj| obj.id.as_str()).collect();
+    
+    let (shared, only_first) = first.into_iter().partition(|obj| second_ids.contains(obj.id.as_str()));
+    let only_second = second.into_iter().filter(|obj| !first_ids.contains(obj.id.as_str())).collect();
+    TechniqueOverlap { shared, only_first, only_second }
+}
+
+/// A group's share of a set of observed techniques
+#[derive(Debug)]
+pub struct GroupMatch<'a> {
+    pub group: &'a AttackObject,
+    /// How many of the observed techniques the group is known to use
+    pub matched: usize,
+    /// How many techniques the group is known to use overall
+    pub known: usize,
+}
+
+/// Ranks groups by how many of the given techniques (STIX ids) they use. Ties go to the group
+/// with the smaller repertoire, whose match is the more specific one.
+pub fn rank_groups_by_techniques<'a>(technique_ids: &[&str], data: &'a AttackData) -> Vec<GroupMatch<'a>> {
+    let mut matched: HashMap<&str, (&AttackObject, usize)> = HashMap::new();
+    for technique_id in technique_ids.iter().collect::<HashSet<_>>() {
+        for group in dedupe_by_id(data.related_sources(technique_id, "uses").filter(|obj| obj.obj_type == "intrusion-set").collect()) {
+            matched.entry(group.id.as_str()).or_insert((group, 0)).1 += 1;
+        }
+    }
+    
+    let mut ranking: Vec<GroupMatch> = matched.into_values()
+        .map(|(group, matched)| GroupMatch { group, matched, known: get_related_techniques(&group.id, data).len() })
+        .collect();
+    ranking.sort_by(|a, b| {
+        b.matched.cmp(&a.matched)
+            .then(a.known.cmp(&b.known))
+            .then_with(|| a.group.name.cmp(&b.group.name))
+    });
+    ranking
+}
+
+/// A technique and how many groups are known to use it
+#[derive(Debug)]
+pub struct TechniqueUsage<'a> {
+    pub technique: &'a AttackObject,
+    pub groups: usize,
+}
+
+/// Current techniques ranked by how many current groups use them, then by MITRE ID, optionally
+/// limited to one tactic. Techniques no group uses are left out.
+pub fn rank_techniques_by_groups<'a>(tactic: Option<&str>, data: &'a AttackData) -> Vec<TechniqueUsage<'a>> {
+    let tactic = tactic.map(normalize_tactic_name);
+    let mut ranking: Vec<TechniqueUsage> = current_techniques(data)
+        .filter(|technique| tactic.as_ref().is_none_or(|tactic| in_tactic(technique, tactic)))
+        .map(|technique| {
+            let groups = dedupe_by_id(data.related_sources(&technique.id, "uses")
+                .filter(|obj| obj.obj_type == "intrusion-set" && obj.revoked != Some(true) && !obj.is_deprecated())
+                .collect());
+            TechniqueUsage { technique, groups: groups.len() }
+        })
+        .filter(|entry| entry.groups > 0)
+        .collect();
+    ranking.sort_by(|a, b| {
+        b.groups.cmp(&a.groups).then_with(|| {
+            compare_mitre_ids(&get_mitre_id(a.technique).unwrap_or_default(), &get_mitre_id(b.technique).unwrap_or_default())
+        })
+    });
+    ranking
+}
+
+/// A group and how many techniques it is known to use
+#[derive(Debug)]
+pub struct GroupActivity<'a> {
+    pub group: &'a AttackObject,
+    pub techniques: usize,
+}
+
+/// Current groups ranked by how many techniques they use, then by name, optionally counting only
+/// the techniques of one tactic. Groups without any such technique are left out.
+pub fn rank_groups_by_technique_count<'a>(tactic: Option<&str>, data: &'a AttackData) -> Vec<GroupActivity<'a>> {
+    let tactic = tactic.map(normalize_tactic_name);
+    let mut ranking: Vec<GroupActivity> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "intrusion-set" && obj.revoked != Some(true) && !obj.is_deprecated())
+        .map(|group| {
+            let techniques = get_related_techniques(&group.id, data)
+                .into_iter()
+                .filter(|technique| tactic.as_ref().is_none_or(|tactic| in_tactic(technique, tactic)))
+                .count();
+            GroupActivity { group, techniques }
+        })
+        .filter(|entry| entry.techniques > 0)
+        .collect();
+    ranking.sort_by(|a, b| b.techniques.cmp(&a.techniques).then_with(|| a.group.name.cmp(&b.group.name)));
+    ranking
+}
+
+/// A mitigation and which of a set of techniques it addresses
+#[derive(Debug)]
+pub struct MitigationCoverage<'a> {
+    pub mitigation: &'a AttackObject,
+    pub covered: Vec<&'a AttackObject>,
+}
+
+/// Every mitigation addressing at least one of the techniques, ranked by how many of them it
+/// covers, then by MITRE ID
+pub fn rank_mitigations_for_techniques<'a>(techniques: &[&'a AttackObject], data: &'a AttackData) -> Vec<MitigationCoverage<'a>> {
+    let mut coverage: HashMap<&str, MitigationCoverage> = HashMap::new();
+    for technique in dedupe_by_id(techniques.to_vec()) {
+        for (mitigation, _) in get_mitigations_for_technique(&technique.id, data) {
+            coverage.entry(mitigation.id.as_str())
+                .or_insert_with(|| MitigationCoverage { mitigation, covered: Vec::new() })
+                .covered
+                .push(technique);
+        }
+    }
+    
+    let mut ranking: Vec<MitigationCoverage> = coverage.into_values().collect();
+    ranking.sort_by(|a, b| {
+        b.covered.len().cmp(&a.covered.len()).then_with(|| {
+            compare_mitre_ids(&get_mitre_id(a.mitigation).unwrap_or_default(), &get_mitre_id(b.mitigation).unwrap_or_default())
+        })
+    });
+    ranking
+}
+
+/// A data source and which of a set of techniques it could help detect
+#[derive(Debug)]
+pub struct DataSourceCoverage<'a> {
+    /// As written in `x_mitre_data_sources`, e.g. `Process: Process Creation`
+    pub data_source: &'a str,
+    pub covered: Vec<&'a AttackObject>,
+}
+
+/// Every data source listed by at least one of the techniques, ranked by how many of them it
+/// could help detect, then by name
+pub fn rank_data_sources<'a>(techniques: &[&'a AttackObject]) -> Vec<DataSourceCoverage<'a>> {
+    let mut coverage: HashMap<&str, DataSourceCoverage> = HashMap::new();
+    for technique in dedupe_by_id(techniques.to_vec()) {
+        let sources: HashSet<&str> = technique.data_sources.iter().flatten().map(String::as_str).collect();
+        for data_source in sources {
+            coverage.entry(data_source)
+                .or_insert_with(|| DataSourceCoverage { data_source, covered: Vec::new() })
+                .covered
+                .push(technique);
+        }
+    }
+    
+    let mut ranking: Vec<DataSourceCoverage> = coverage.into_values().collect();
+    ranking.sort_by(|a, b| b.covered.len().cmp(&a.covered.len()).then_with(|| a.data_source.cmp(b.data_source)));
+    ranking
+}
+
+/// Detection coverage of one tactic
+#[derive(Debug)]
+pub struct TacticCoverage<'a> {
+    /// Tactic shortname, e.g. `initial-access`
+    pub tactic: &'a str,
+    pub total: usize,
+    /// Techniques under this tactic that are not covered
+    pub missing: Vec<&'a AttackObject>,
+}
+
+impl TacticCoverage<'_> {
+    pub fn covered(&self) -> usize {
+        self.total - self.missing.len()
+    }
+}
+
+/// Per-tactic coverage of current techniques given the covered STIX ids, in kill-chain order
+pub fn tactic_coverage<'a>(covered: &HashSet<&str>, data: &'a AttackData) -> Vec<TacticCoverage<'a>> {
+    let mut by_tactic: HashMap<&str, TacticCoverage> = HashMap::new();
+    for technique in current_techniques(data) {
+        for tactic in technique_tactics(technique) {
+            let entry = by_tactic.entry(tactic).or_insert(TacticCoverage { tactic, total: 0, missing: Vec::new() });
+            entry.total += 1;
+            if !covered.contains(technique.id.as_str()) {
+                entry.missing.push(technique);
+            }
+        }
+    }
+    
+    let mut coverage: Vec<TacticCoverage> = by_tactic.into_values().collect();
+    coverage.sort_by_key(|entry| (kill_chain_position(entry.tactic), entry.tactic));
+    coverage
+}
+
+/// Coverage of the tactics a mitigation addresses at least one technique of, in kill-chain order;
+/// `missing` holds the techniques of those tactics the mitigation leaves unaddressed
+pub fn mitigation_gaps<'a>(mitigation_id: &str, data: &'a AttackData) -> Vec<TacticCoverage<'a>> {
+    let covered: HashSet<&str> = get_mitigated_techniques(mitigation_id, data)
+        .into_iter()
+        .map(|technique| technique.id.as_str())
+        .collect();
+    tactic_coverage(&covered, data)
+        .into_iter()
+        .filter(|entry| entry.covered() > 0)
+        .collect()
+}
+
+/// Counts the distinct techniques each mitigation is linked to by `mitigates` relationships
+pub fn get_mitigation_coverage(data: &AttackData) -> HashMap<&str, usize> {
+    let technique_ids: HashSet<&str> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .map(|obj| obj.id.as_str())
+        .collect();
+    
+    let mut mitigated: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for obj in &data.objects {
+        if obj.obj_type == "relationship"
+            && let (Some(source_ref), Some(target_ref), Some(relationship_type)) =
+                (&obj.source_ref, &obj.target_ref, &obj.relationship_type)
+            && relationship_type == "mitigates"
+            && technique_ids.contains(target_ref.as_str()) {
+            mitigated.entry(source_ref.as_str())
+                .or_default()
+                .insert(target_ref.as_str());
+        }
+    }
+    
+    mitigated.into_iter()
+        .map(|(mitigation_id, techniques)| (mitigation_id, techniques.len()))
+        .collect()
+}
+
+pub fn find_technique_by_id<'a>(id: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    data.find_by_mitre_id(id).find(|obj| obj.obj_type == "attack-pattern")
+}
+
+/// Techniques that are not revoked whose MITRE ID starts with `prefix`, ignoring case, sorted by ID.
+/// "T1055." gives the sub-techniques of T1055.
+pub fn find_techniques_by_id_prefix<'a>(prefix: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let prefix = prefix.trim().to_uppercase();
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+    let mut techniques: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern" && obj.revoked != Some(true))
+        .filter(|obj| get_mitre_id(obj).is_some_and(|mitre_id| mitre_id.to_uppercase().starts_with(&prefix)))
+        .collect();
+    sort_objects(&mut techniques, SortOrder::Id);
+    techniques
+}
+
+// A query shaped like "G0016" is looked up by MITRE ID before any name matching
+fn find_group_by_mitre_id<'a>(query: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    let digits = query.trim().strip_prefix(['G', 'g'])?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    data.find_by_mitre_id(query.trim()).find(|obj| obj.obj_type == "intrusion-set")
+}
+
+/// Matches an exact G-ID first, otherwise the MITRE ID or a case-insensitive substring of the name or any alias
+pub fn find_groups_by_name<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    if let Some(obj) = find_group_by_mitre_id(name, data) {
+        return vec![obj];
+    }
+    
+    let name_lower = name.to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "intrusion-set"
+                && (get_mitre_id(obj).is_some_and(|mitre_id| mitre_id.eq_ignore_ascii_case(name.trim()))
+                    || obj.name.as_ref().is_some_and(|obj_name| obj_name.to_lowercase().contains(&name_lower))
+                    || obj.aliases.as_ref().is_some_and(|aliases| {
+                        aliases.iter().any(|alias| alias.to_lowercase().contains(&name_lower))
+                    }))
+        })
+        .collect()
+}
+
+/// Groups whose name or one of whose aliases is exactly `name`, ignoring case, or the group with that G-ID
+pub fn find_groups_by_exact_name<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    if let Some(obj) = find_group_by_mitre_id(name, data) {
+        return vec![obj];
+    }
+    
+    let name_lower = name.trim().to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "intrusion-set"
+                && (obj.name.as_ref().is_some_and(|obj_name| obj_name.to_lowercase() == name_lower)
+                    || obj.aliases.as_ref().is_some_and(|aliases| {
+                        aliases.iter().any(|alias| alias.to_lowercase() == name_lower)
+                    }))
+        })
+        .collect()
+}
+
+pub fn find_group<'a>(query: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    let query_lower = query.trim().to_lowercase();
+    data.objects.iter().find(|obj| {
+        obj.obj_type == "intrusion-set"
+            && (obj.name.as_ref().is_some_and(|name| name.to_lowercase() == query_lower)
+                || get_mitre_id(obj).is_some_and(|mitre_id| mitre_id.to_lowercase() == query_lower)
+                || obj.aliases.as_ref().is_some_and(|aliases| {
+                    aliases.iter().any(|alias| alias.to_lowercase() == query_lower)
+                }))
+    })
+}
+
+/// Enterprise tactic shortnames in the order an intrusion progresses
+pub const KILL_CHAIN_ORDER: &[&str] = &[
+    "reconnaissance",
+    "resource-development",
+    "initial-access",
+    "execution",
+    "persistence",
+    "privilege-escalation",
+    "defense-evasion",
+    "credential-access",
+    "discovery",
+    "lateral-movement",
+    "collection",
+    "command-and-control",
+    "exfiltration",
+    "impact",
+];
+
+/// Position of a tactic shortname in the kill chain; unknown tactics sort last
+pub fn kill_chain_position(shortname: &str) -> usize {
+    KILL_CHAIN_ORDER.iter()
+        .position(|phase| *phase == shortname)
+        .unwrap_or(KILL_CHAIN_ORDER.len())
+}
+
+/// Tactic shortnames in the order the bundle's matrix lists them, else `KILL_CHAIN_ORDER`
+pub fn tactic_sequence(data: &AttackData) -> Vec<&str> {
+    let from_matrix: Vec<&str> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "x-mitre-matrix")
+        .find_map(|matrix| matrix.tactic_refs.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|tactic_ref| data.get(tactic_ref)?.shortname.as_deref())
+        .collect();
+    if from_matrix.is_empty() {
+        return KILL_CHAIN_ORDER.to_vec();
+    }
+    from_matrix
+}
+
+pub fn get_tactics_in_kill_chain_order(data: &AttackData) -> Vec<&AttackObject> {
+    let mut tactics: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "x-mitre-tactic")
+        .collect();
+    tactics.sort_by_key(|tactic| {
+        (kill_chain_position(tactic.shortname.as_deref().unwrap_or_default()), tactic.name.clone())
+    });
+    tactics
+}
+
+/// Number of techniques under each kill-chain phase, keyed by tactic shortname
+pub fn get_tactic_technique_counts(data: &AttackData) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for obj in &data.objects {
+        if obj.obj_type == "attack-pattern"
+            && let Some(phases) = &obj.kill_chain_phases {
+            for phase in phases {
+                if phase.is_attack() {
+                    *counts.entry(phase.phase_name.as_str()).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// Common shorthands for tactics, mapped to the official shortname
+pub const TACTIC_ALIASES: &[(&str, &str)] = &[
+    ("c2", "command-and-control"),
+    ("cnc", "command-and-control"),
+    ("c&c", "command-and-control"),
+    ("recon", "reconnaissance"),
+    ("privesc", "privilege-escalation"),
+    ("priv-esc", "privilege-escalation"),
+    ("evasion", "defense-evasion"),
+    ("creds", "credential-access"),
+    ("lateral", "lateral-movement"),
+    ("exfil", "exfiltration"),
+];
+
+/// Normalizes tactic names and shortnames so "Privilege Escalation" and "privilege-escalation" compare equal.
+/// Aliases from `TACTIC_ALIASES` normalize to their tactic, so "C2" equals "command-and-control".
+pub fn normalize_tactic_name(name: &str) -> String {
+    let normalized = name.trim().to_lowercase().replace("-", "_").replace(" ", "_");
+    TACTIC_ALIASES.iter()
+        .find(|(alias, _)| alias.replace('-', "_") == normalized)
+        .map_or(normalized, |(_, shortname)| shortname.replace('-', "_"))
+}
+
+/// Tactics whose name or shortname contains `name`. The shortname is the phase name techniques use in
+/// their `kill_chain_phases` (e.g. "command-and-control"), and `name` may also be one of `TACTIC_ALIASES`.
+pub fn find_tactics_by_name<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let name_normalized = normalize_tactic_name(name);
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "x-mitre-tactic"
+                && (obj.name.as_ref().is_some_and(|obj_name| normalize_tactic_name(obj_name).contains(&name_normalized))
+                    || obj.shortname.as_ref().is_some_and(|shortname| normalize_tactic_name(shortname).contains(&name_normalized)))
+        })
+        .collect()
+}
+
+/// Techniques that are not revoked, sorted by MITRE ID, optionally limited to one tactic (by name or
+/// shortname, e.g. "Privilege Escalation" or "privilege-escalation") and to the given platforms.
+/// Sub-techniques are only included when `include_subs` is set.
+pub fn list_techniques<'a>(
+    tactic: Option<&str>,
+    platforms: &[String],
+    platform_match: PlatformMatch,
+    include_subs: bool,
+    data: &'a AttackData,
+) -> Vec<&'a AttackObject> {
+    let mut techniques = filter_techniques(tactic, platforms, platform_match, include_subs, data);
+    techniques.retain(|obj| obj.revoked != Some(true));
+    techniques
+}
+
+/// Like `list_techniques`, but only the deprecated and revoked techniques, for auditing what MITRE has sunset
+pub fn list_sunset_techniques<'a>(
+    tactic: Option<&str>,
+    platforms: &[String],
+    platform_match: PlatformMatch,
+    include_subs: bool,
+    data: &'a AttackData,
+) -> Vec<&'a AttackObject> {
+    let mut techniques = filter_techniques(tactic, platforms, platform_match, include_subs, data);
+    techniques.retain(|obj| obj.is_sunset());
+    techniques
+}
+
+fn filter_techniques<'a>(
+    tactic: Option<&str>,
+    platforms: &[String],
+    platform_match: PlatformMatch,
+    include_subs: bool,
+    data: &'a AttackData,
+) -> Vec<&'a AttackObject> {
+    let tactic = tactic.map(normalize_tactic_name);
+    let mut techniques: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern")
+        .filter(|obj| include_subs || obj.is_subtechnique != Some(true))
+        .filter(|obj| tactic.as_ref().is_none_or(|tactic| in_tactic(obj, tactic)))
+        .filter(|obj| matches_platforms(obj, platforms, platform_match))
+        .collect();
+    sort_objects(&mut techniques, SortOrder::Id);
+    techniques
+}
+
+// Whether one of the technique's ATT&CK phases is the tactic, given as `normalize_tactic_name` returns it
+fn in_tactic(technique: &AttackObject, tactic: &str) -> bool {
+    technique.kill_chain_phases.iter().flatten().any(|phase| {
+        phase.is_attack() && normalize_tactic_name(&phase.phase_name) == tactic
+    })
+}
+
+/// External reference sources whose `description` holds a name the object was known by before a rename
+pub const PREVIOUS_NAME_SOURCES: &[&str] = &["previous-name", "former-name"];
+
+/// Names a technique went by before being renamed: its `aliases`/`x_mitre_aliases`, and the
+/// descriptions of `PREVIOUS_NAME_SOURCES` references
+pub fn previous_names(obj: &AttackObject) -> Vec<&str> {
+    let aliases = obj.aliases.iter().chain(&obj.software_aliases).flatten().map(String::as_str);
+    let referenced = obj.external_references.iter()
+        .flatten()
+        .filter(|reference| PREVIOUS_NAME_SOURCES.contains(&reference.source_name.as_str()))
+        .filter_map(|reference| reference.description.as_deref());
+    let mut names: Vec<&str> = Vec::new();
+    for name in aliases.chain(referenced) {
+        if Some(name) != obj.name.as_deref() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Techniques whose name or a previous name contains `name`, ignoring case
+pub fn find_techniques_by_name<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let name_lower = name.to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "attack-pattern"
+                && obj.name.iter().map(String::as_str).chain(previous_names(obj))
+                    .any(|obj_name| obj_name.to_lowercase().contains(&name_lower))
+        })
+        .collect()
+}
+
+/// Techniques whose whole name, or a whole previous name, is `name`, ignoring case
+pub fn find_techniques_by_exact_name<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let name_lower = name.trim().to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "attack-pattern"
+                && obj.name.iter().map(String::as_str).chain(previous_names(obj))
+                    .any(|obj_name| obj_name.to_lowercase() == name_lower)
+        })
+        .collect()
+}
+
+/// Current techniques observable through a data source. Matches a whole entry such as
+/// "Process: Process Creation", or just the data source part ("Process") to cover all its components.
+pub fn find_techniques_by_data_source<'a>(name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let name_lower = name.trim().to_lowercase();
+    let matches = |entry: &String| {
+        let entry = entry.to_lowercase();
+        entry == name_lower || entry.split(':').next().is_some_and(|source| source.trim() == name_lower)
+    };
+    current_techniques(data)
+        .filter(|obj| obj.data_sources.iter().flatten().any(matches))
+        .collect()
+}
+
+/// Techniques that are neither revoked nor deprecated
+pub fn current_techniques(data: &AttackData) -> impl Iterator<Item = &AttackObject> {
+    data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern" && obj.revoked != Some(true) && !obj.is_deprecated())
+}
+
+/// Matches an exact S-ID first, otherwise a case-insensitive substring of the name or any alias
+pub fn find_software<'a>(id_or_name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    if let Some(obj) = data.find_by_mitre_id(id_or_name).find(|obj| is_software(obj)) {
+        return vec![obj];
+    }
+    
+    let name_lower = id_or_name.to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            is_software(obj)
+                && (obj.name.as_ref().is_some_and(|obj_name| obj_name.to_lowercase().contains(&name_lower))
+                    || obj.software_aliases.as_ref().is_some_and(|aliases| {
+                        aliases.iter().any(|alias| alias.to_lowercase().contains(&name_lower))
+                    }))
+        })
+        .collect()
+}
+
+/// Matches an exact C-ID first, otherwise a case-insensitive substring of the name
+pub fn find_campaigns<'a>(id_or_name: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    if let Some(obj) = data.find_by_mitre_id(id_or_name).find(|obj| obj.obj_type == "campaign") {
+        return vec![obj];
+    }
+    
+    let name_lower = id_or_name.to_lowercase();
+    data.objects
+        .iter()
+        .filter(|obj| {
+            obj.obj_type == "campaign"
+                && obj.name.as_ref().is_some_and(|obj_name| obj_name.to_lowercase().contains(&name_lower))
+        })
+        .collect()
+}
+
+pub fn find_mitigation_by_id<'a>(id: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    data.find_by_mitre_id(id).find(|obj| obj.obj_type == "course-of-action")
+}
+
+/// Resolves a STIX id or a MITRE ID to one object, preferring current objects over revoked ones
+pub fn find_object<'a>(id: &str, data: &'a AttackData) -> Option<&'a AttackObject> {
+    data.get(id).or_else(|| {
+        let mut found: Vec<&AttackObject> = data.find_by_mitre_id(id).collect();
+        found.sort_by_key(|obj| obj.revoked == Some(true));
+        found.first().copied()
+    })
+}
+
+/// The ATT&CK release a bundle holds, e.g. "15.1": the collection's version, else the matrix's
+pub fn attack_version(data: &AttackData) -> Option<&str> {
+    ["x-mitre-collection", "x-mitre-matrix"].into_iter().find_map(|obj_type| {
+        data.objects
+            .iter()
+            .find(|obj| obj.obj_type == obj_type)
+            .and_then(|obj| obj.version.as_deref())
+    })
+}
+
+/// The most recent `modified` timestamp in the bundle, i.e. how fresh the local copy is
+pub fn last_modified(data: &AttackData) -> Option<&str> {
+    // STIX timestamps are all UTC in the same format, so they order as strings
+    data.objects.iter().filter_map(|obj| obj.modified.as_deref()).max()
+}
+
+/// Techniques, groups, software, mitigations and tactics modified on or after `since` (YYYY-MM-DD),
+/// by kind and newest first within each. Objects without a `modified` timestamp are skipped.
+pub fn modified_since<'a>(since: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    let mut found: Vec<&AttackObject> = data.objects
+        .iter()
+        .filter(|obj| ObjectKind::of(obj).is_some())
+        .filter(|obj| obj.modified.as_deref().is_some_and(|modified| modified.get(..10).unwrap_or(modified) >= since))
+        .collect();
+    found.sort_by(|a, b| ObjectKind::of(a).cmp(&ObjectKind::of(b)).then_with(|| b.modified.cmp(&a.modified)));
+    found
+}
+
+/// All relationships touching an object, each listed once even if it links the object to itself
+pub fn get_relationships<'a>(stix_id: &str, data: &'a AttackData) -> Vec<&'a AttackObject> {
+    dedupe_by_id(data.relationships_of(stix_id).collect())
+}
+
+/// The object's page on attack.mitre.org, from its `mitre-attack` reference or built from its ID
+pub fn attack_url(obj: &AttackObject) -> Option<String> {
+    let mitre_reference = obj.external_references
+        .iter()
+        .flatten()
+        .find(|reference| reference.source_name == "mitre-attack");
+    mitre_reference
+        .and_then(|reference| reference.url.clone())
+        .or_else(|| canonical_attack_url(&obj.obj_type, mitre_reference?.external_id.as_deref()?))
+}
+
+/// The attack.mitre.org URL for an ID, e.g. `https://attack.mitre.org/techniques/T1055/001/`
+pub fn canonical_attack_url(obj_type: &str, mitre_id: &str) -> Option<String> {
+    let section = match obj_type {
+        "attack-pattern" => "techniques",
+        "intrusion-set" => "groups",
+        "malware" | "tool" => "software",
+        "course-of-action" => "mitigations",
+        "x-mitre-tactic" => "tactics",
+        "campaign" => "campaigns",
+        "x-mitre-data-source" => "datasources",
+        _ => return None,
+    };
+    Some(format!("https://attack.mitre.org/{}/{}/", section, mitre_id.replace('.', "/")))
+}
+
+/// What kind of object this is in ATT&CK terms, e.g. "sub-technique" or "group"; unfamiliar STIX
+/// types come back unchanged
+pub fn object_type_name(obj: &AttackObject) -> &str {
+    match obj.obj_type.as_str() {
+        "attack-pattern" if obj.is_subtechnique == Some(true) => "sub-technique",
+        "attack-pattern" => "technique",
+        "intrusion-set" => "group",
+        "course-of-action" => "mitigation",
+        "x-mitre-tactic" => "tactic",
+        "x-mitre-data-source" => "data-source",
+        "x-mitre-data-component" => "data-component",
+        "x-mitre-matrix" => "matrix",
+        other => other,
+    }
+}
+
+pub fn is_software(obj: &AttackObject) -> bool {
+    obj.obj_type == "malware" || obj.obj_type == "tool"
+}
+
+/// The part of an object a search term was found in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatchField {
+    Name,
+    Alias,
+    Description,
+}
+
+impl MatchField {
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchField::Name => "name",
+            MatchField::Alias => "alias",
+            MatchField::Description => "description",
+        }
+    }
+}
+
+// The first field of `obj` containing `term_lower`, which must already be lowercase
+fn matching_field(obj: &AttackObject, term_lower: &str) -> Option<MatchField> {
+    let matches = |text: &String| text.to_lowercase().contains(term_lower);
+    if obj.name.as_ref().is_some_and(matches) {
+        Some(MatchField::Name)
+    } else if obj.aliases.iter().chain(&obj.software_aliases).flatten().any(matches) {
+        Some(MatchField::Alias)
+    } else if obj.description.as_ref().is_some_and(matches) {
+        Some(MatchField::Description)
+    } else {
+        None
+    }
+}
+
+fn searchable(kind: Option<ObjectKind>, data: &AttackData) -> impl Iterator<Item = &AttackObject> {
+    data.objects.iter().filter(move |obj| match ObjectKind::of(obj) {
+        Some(obj_kind) => kind.is_none_or(|kind| kind == obj_kind),
+        None => false,
+    })
+}
+
+/// An object found by `search_terms` and where each of its matching terms was found
+#[derive(Debug)]
+pub struct SearchMatch<'a> {
+    pub object: &'a AttackObject,
+    /// Matching terms in query order, with the first field containing each
+    pub terms: Vec<(String, MatchField)>,
+}
+
+/// Splits `query` on whitespace and finds the objects containing every term in their name, aliases
+/// or description, in any order and ignoring case. With `any`, one matching term is enough.
+pub fn search_terms<'a>(query: &str, any: bool, kind: Option<ObjectKind>, data: &'a AttackData) -> Vec<SearchMatch<'a>> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split_whitespace().map(str::to_lowercase) {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    
+    searchable(kind, data)
+        .filter_map(|obj| {
+            let matched: Vec<(String, MatchField)> = terms.iter()
+                .filter_map(|term| matching_field(obj, term).map(|field| (term.clone(), field)))
+                .collect();
+            let found = if any { !matched.is_empty() } else { matched.len() == terms.len() };
+            found.then_some(SearchMatch { object: obj, terms: matched })
+        })
+        .collect()
+}
+
+/// Where a query matched inside a longer text, with up to `context` characters of text either side
+#[derive(Debug, PartialEq)]
+pub struct Snippet<'a> {
+    pub before: &'a str,
+    pub matched: &'a str,
+    pub after: &'a str,
+    /// Whether text was cut off before `before` / after `after`
+    pub clipped_start: bool,
+    pub clipped_end: bool,
+}
+
+// Byte range of the first case-insensitive occurrence of `query`, compared char by char so
+// offsets stay valid in `text` even where lowercasing changes a character's length
+fn find_case_insensitive(text: &str, query: &str) -> Option<(usize, usize)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+    
+    'start: for (start, _) in text.char_indices() {
+        let mut wanted = query.iter().peekable();
+        for (offset, c) in text[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if wanted.next() != Some(&lower) {
+                    continue 'start;
+                }
+            }
+            if wanted.peek().is_none() {
+                return Some((start, start + offset + c.len_utf8()));
+            }
+        }
+        return None;
+    }
+    None
+}
+
+pub fn find_snippet<'a>(text: &'a str, query: &str, context: usize) -> Option<Snippet<'a>> {
+    let (start, end) = find_case_insensitive(text, query)?;
+    let before_start = text[..start].char_indices().rev().take(context).last().map_or(start, |(i, _)| i);
+    let after_end = text[end..].char_indices().nth(context).map_or(text.len(), |(i, _)| end + i);
+    
+    Some(Snippet {
+        before: &text[before_start..start],
+        matched: &text[start..end],
+        after: &text[end..after_end],
+        clipped_start: before_start > 0,
+        clipped_end: after_end < text.len(),
+    })
+}
+
+/// Techniques whose detection guidance mentions `query` (case-insensitive), with the first match in context
+pub fn search_detections<'a>(query: &str, context: usize, data: &'a AttackData) -> Vec<(&'a AttackObject, Snippet<'a>)> {
+    data.objects
+        .iter()
+        .filter(|obj| obj.obj_type == "attack-pattern" && obj.revoked != Some(true))
+        .filter_map(|obj| Some((obj, find_snippet(obj.detection.as_deref()?, query, context)?)))
+        .collect()
+}
+
+/// How many of the techniques apply to each platform, most common first (ties by name)
+pub fn platform_coverage<'a>(techniques: &[&'a AttackObject]) -> Vec<(&'a str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for technique in techniques {
+        for platform in technique.platforms.iter().flatten() {
+            *counts.entry(platform.as_str()).or_insert(0) += 1;
+        }
+    }
+    let mut coverage: Vec<(&str, usize)> = counts.into_iter().collect();
+    coverage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    coverage
+}
+
+/// Whether the object runs on any (or, with `PlatformMatch::All`, every one) of the given platforms
+/// (case-insensitive); an empty filter matches everything
+pub fn matches_platforms(obj: &AttackObject, platforms: &[String], mode: PlatformMatch) -> bool {
+    let runs_on = |platform: &String| {
+        obj.platforms.iter().flatten().any(|obj_platform| platform.eq_ignore_ascii_case(obj_platform))
+    };
+    match mode {
+        _ if platforms.is_empty() => true,
+        PlatformMatch::Any => platforms.iter().any(runs_on),
+        PlatformMatch::All => platforms.iter().all(runs_on),
+    }
+}
+
+/// Below this Jaro-Winkler similarity a name is too different to be worth suggesting
+const MIN_SUGGESTION_SIMILARITY: f64 = 0.7;
+
+/// Closest names to a query among objects of one kind, for "Did you mean" hints.
+/// Aliases count as names, so a typo in "Fancy Bear" still finds APT28.
+pub fn suggest_similar<'a>(query: &str, kind: ObjectKind, data: &'a AttackData, limit: usize) -> Vec<(&'a AttackObject, &'a str)> {
+    let query_lower = query.to_lowercase();
+    let mut scored: Vec<(f64, &AttackObject, &str)> = Vec::new();
+    
+    for obj in &data.objects {
+        if ObjectKind::of(obj) != Some(kind) {
+            continue;
+        }
+        let best = obj.name.iter()
+            .chain(obj.aliases.iter().flatten())
+            .chain(obj.software_aliases.iter().flatten())
+            .map(|name| (strsim::jaro_winkler(&query_lower, &name.to_lowercase()), name.as_str()))
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((score, name)) = best
+            && score >= MIN_SUGGESTION_SIMILARITY {
+            scored.push((score, obj, name));
+        }
+    }
+    
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.2.cmp(b.2)));
+    scored.into_iter()
+        .take(limit)
+        .map(|(_, obj, name)| (obj, name))
+        .collect()
+}
+
+/// Drops repeated objects (same STIX id), keeping the first occurrence.
+/// Duplicate relationships in the bundle would otherwise list the same object twice.
+pub fn dedupe_by_id(objects: Vec<&AttackObject>) -> Vec<&AttackObject> {
+    let mut seen = HashSet::new();
+    objects.into_iter()
+        .filter(|obj| seen.insert(obj.id.as_str()))
+        .collect()
+}
+
+/// Follows `revoked-by` links until reaching an object that is still current
+pub fn resolve_revoked<'a>(obj: &'a AttackObject, data: &'a AttackData) -> &'a AttackObject {
+    let mut current = obj;
+    let mut visited = HashSet::new();
+    while current.revoked == Some(true) && visited.insert(current.id.as_str()) {
+        match data.related_targets(&current.id, "revoked-by").next() {
+            Some(replacement) => current = replacement,
+            None => break,
+        }
+    }
+    current
+}
+
+/// What a revoked object was replaced by, following `revoked-by` links to a current object;
+/// `None` for objects that are not revoked or whose replacement is missing from the bundle
+pub fn replacement_of<'a>(obj: &'a AttackObject, data: &'a AttackData) -> Option<&'a AttackObject> {
+    if obj.revoked != Some(true) {
+        return None;
+    }
+    let replacement = resolve_revoked(obj, data);
+    (replacement.id != obj.id).then_some(replacement)
+}
+
+/// Turns a kill-chain phase like "privilege-escalation" into "Privilege Escalation".
+/// Works on chars rather than byte offsets and skips empty segments, so odd names can't panic.
+pub fn title_case_phase_name(phase_name: &str) -> String {
+    phase_name
+        .split(['-', ' '])
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The technique's ATT&CK tactic shortnames, in the order the bundle lists them
+pub fn technique_tactics(technique: &AttackObject) -> Vec<&str> {
+    technique.kill_chain_phases
+        .iter()
+        .flatten()
+        .filter(|phase| phase.is_attack())
+        .map(|phase| phase.phase_name.as_str())
+        .collect()
+}
+
+/// Buckets techniques under their capitalized tactic names, sorted alphabetically
+pub fn group_techniques_by_tactic<'a>(techniques: &[&'a AttackObject]) -> Vec<(String, Vec<&'a AttackObject>)> {
+    let mut tactics_map: HashMap<String, Vec<&AttackObject>> = HashMap::new();
+    
+    for technique in techniques {
+        if let Some(phases) = &technique.kill_chain_phases {
+            for phase in phases {
+                if phase.is_attack() {
+                    tactics_map.entry(title_case_phase_name(&phase.phase_name))
+                        .or_default()
+                        .push(technique);
+                }
+            }
+        }
+    }
+    
+    let mut sorted_tactics: Vec<_> = tactics_map.into_iter().collect();
+    sorted_tactics.sort_by(|a, b| a.0.cmp(&b.0));
+    sorted_tactics
+}
+
+/// `group_techniques_by_tactic` with the tactics in `order`; in kill-chain order, tactics the
+/// matrix doesn't list come last, alphabetically
+pub fn group_techniques_in_order<'a>(
+    techniques: &[&'a AttackObject],
+    order: TacticOrder,
+    data: &AttackData,
+) -> Vec<(String, Vec<&'a AttackObject>)> {
+    let mut groups = group_techniques_by_tactic(techniques);
+    if order == TacticOrder::Killchain {
+        let sequence: Vec<String> = tactic_sequence(data).into_iter().map(title_case_phase_name).collect();
+        groups.sort_by_key(|(tactic, _)| sequence.iter().position(|name| name == tactic).unwrap_or(sequence.len()));
+    }
+    groups
+}
+
+/// Description text without `(Citation: ...)` markers, and with `[text](url)` links reduced to their text
+pub fn plain_description(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut rest = text;
+    loop {
+        let citation = rest.find("(Citation: ");
+        let link = rest.find('[').filter(|&start| {
+            rest[start..].find("](").is_some_and(|close| !rest[start + 1..start + close].contains(['[', ']']))
+        });
+        let Some(start) = [citation, link].into_iter().flatten().min() else {
+            break;
+        };
+        let Some(end) = rest[start..].find(')').map(|offset| start + offset) else {
+            break;
+        };
+        
+        plain.push_str(&rest[..start]);
+        if Some(start) == link {
+            let close = rest[start..].find("](").map_or(start, |offset| start + offset);
+            plain.push_str(&rest[start + 1..close]);
+        }
+        rest = &rest[end + 1..];
+    }
+    plain.push_str(rest);
+    plain
+}
+
+// Words whose trailing period rarely ends a sentence in ATT&CK prose
+const ABBREVIATIONS: &[&str] = &["etc", "vs", "cf", "approx", "inc", "corp", "ltd", "co", "mr", "ms", "dr", "st", "no"];
+
+// Byte offset of the period ending the first sentence, skipping abbreviations ("e.g.", "Inc."),
+// initials ("J. Smith") and dotted acronyms ("U.S.")
+fn sentence_end(text: &str) -> Option<usize> {
+    text.match_indices(". ").map(|(i, _)| i).find(|&i| {
+        let word = text[..i].rsplit(' ').next().unwrap_or_default().trim_start_matches(['(', '"', '\'']);
+        let is_initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+        !is_initial && !word.contains('.') && !ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+    })
+}
+
+/// The first sentence of a description, at most `max_chars` long
+pub fn first_sentence(text: &str, max_chars: usize) -> String {
+    let plain = plain_description(text);
+    let collapsed = plain.split_whitespace().collect::<Vec<_>>().join(" ");
+    let sentence = match sentence_end(&collapsed) {
+        Some(end) => &collapsed[..=end],
+        None => collapsed.as_str(),
+    };
+    
+    if sentence.chars().count() <= max_chars {
+        return sentence.to_string();
+    }
+    let mut capped: String = sentence.chars().take(max_chars.saturating_sub(1)).collect();
+    capped.truncate(capped.trim_end().len());
+    capped.push('…');
+    capped
+}
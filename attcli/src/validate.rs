@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{get_mitre_id, AttackData, AttackObject, ObjectKind};
+
+/// A structural problem with one object of a bundle
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    /// A relationship's `source_ref` names an object that is not in the bundle
+    MissingSource(String),
+    /// A relationship's `target_ref` names an object that is not in the bundle
+    MissingTarget(String),
+    /// A technique without any `kill_chain_phases`, so it belongs to no tactic
+    NoKillChainPhases,
+    /// Another current object already carries this MITRE ID
+    DuplicateMitreId(String),
+    /// A technique, group, software, mitigation, tactic or campaign without a `mitre-attack` reference
+    NoMitreReference,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::MissingSource(id) => write!(f, "source_ref {} is not in the bundle", id),
+            Problem::MissingTarget(id) => write!(f, "target_ref {} is not in the bundle", id),
+            Problem::NoKillChainPhases => write!(f, "technique has no kill_chain_phases"),
+            Problem::DuplicateMitreId(mitre_id) => write!(f, "MITRE ID {} is used by another object", mitre_id),
+            Problem::NoMitreReference => write!(f, "no mitre-attack external reference"),
+        }
+    }
+}
+
+pub struct Issue<'a> {
+    pub object: &'a AttackObject,
+    pub problem: Problem,
+}
+
+/// Checks the invariants lookups rely on, which a corrupted or partial download breaks.
+/// Revoked and deprecated objects may legitimately share IDs or lack fields, so only
+/// their relationships are checked.
+pub fn validate_bundle(data: &AttackData) -> Vec<Issue<'_>> {
+    let mut issues = Vec::new();
+    let current = |obj: &&AttackObject| obj.revoked != Some(true) && !obj.is_deprecated();
+    
+    for obj in data.objects.iter().filter(|obj| obj.obj_type == "relationship") {
+        if let Some(source_ref) = &obj.source_ref
+            && data.get(source_ref).is_none() {
+            issues.push(Issue { object: obj, problem: Problem::MissingSource(source_ref.clone()) });
+        }
+        if let Some(target_ref) = &obj.target_ref
+            && data.get(target_ref).is_none() {
+            issues.push(Issue { object: obj, problem: Problem::MissingTarget(target_ref.clone()) });
+        }
+    }
+    
+    for obj in data.objects.iter().filter(current) {
+        if obj.obj_type == "attack-pattern" && obj.kill_chain_phases.as_ref().is_none_or(Vec::is_empty) {
+            issues.push(Issue { object: obj, problem: Problem::NoKillChainPhases });
+        }
+    }
+    
+    let mut seen_ids: HashSet<String> = HashSet::new();
+    for obj in data.objects.iter().filter(current) {
+        match get_mitre_id(obj) {
+            Some(mitre_id) if !seen_ids.insert(mitre_id.to_uppercase()) => {
+                issues.push(Issue { object: obj, problem: Problem::DuplicateMitreId(mitre_id) });
+            },
+            Some(_) => {},
+            None if ObjectKind::of(obj).is_some() || obj.obj_type == "campaign" => {
+                issues.push(Issue { object: obj, problem: Problem::NoMitreReference });
+            },
+            None => {},
+        }
+    }
+    issues
+}
@@ -0,0 +1,144 @@
+use serde_json::{Map, Value};
+
+/// Renders JSON-shaped data as block-style YAML, e.g. objects serialized with `serde_json::to_value`.
+///
+/// Keys keep their serialized names (`x_mitre_platforms` etc.), so the output reads back into the
+/// same objects. Null fields are left out of mappings, which reads back the same for `Option` fields.
+/// Multi-line strings become literal blocks so descriptions stay readable and diff line by line.
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Array(items) if !items.is_empty() => write_sequence(&mut out, items, 0),
+        Value::Object(map) if has_fields(map) => write_mapping(&mut out, map, 0),
+        scalar => {
+            out.push_str(&scalar_text(scalar, 0));
+            out.push('\n');
+        },
+    }
+    out
+}
+
+fn has_fields(map: &Map<String, Value>) -> bool {
+    map.values().any(|value| !value.is_null())
+}
+
+fn write_mapping(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    for (key, value) in map.iter().filter(|(_, value)| !value.is_null()) {
+        out.push_str(&" ".repeat(indent));
+        out.push_str(&key_text(key));
+        out.push(':');
+        write_nested(out, value, indent + 2);
+    }
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        let mut rendered = String::new();
+        write_nested(&mut rendered, item, indent + 2);
+        out.push_str(&" ".repeat(indent));
+        // A nested block starts on the dash's line: "- key: value" rather than "-\n  key: value"
+        match rendered.strip_prefix('\n') {
+            Some(block) => {
+                out.push_str("- ");
+                out.push_str(&block[indent + 2..]);
+            },
+            None => {
+                out.push('-');
+                out.push_str(&rendered);
+            },
+        }
+    }
+}
+
+// Writes what follows `key:` or `-`: a scalar on the same line, or a block on the following ones
+fn write_nested(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Object(map) if has_fields(map) => {
+            out.push('\n');
+            write_mapping(out, map, indent);
+        },
+        Value::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_sequence(out, items, indent);
+        },
+        scalar => {
+            out.push(' ');
+            out.push_str(&scalar_text(scalar, indent));
+            out.push('\n');
+        },
+    }
+}
+
+fn scalar_text(value: &Value, indent: usize) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => string_text(text, indent),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+fn string_text(text: &str, indent: usize) -> String {
+    if is_plain(text) {
+        text.to_string()
+    } else if let Some(block) = literal_block(text, indent) {
+        block
+    } else {
+        // JSON string escapes are all valid in a YAML double-quoted scalar
+        serde_json::to_string(text).unwrap_or_default()
+    }
+}
+
+fn key_text(key: &str) -> String {
+    if is_plain(key) {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap_or_default()
+    }
+}
+
+// Strings that read back as the same string without quotes
+fn is_plain(text: &str) -> bool {
+    let Some(first) = text.chars().next() else {
+        return false;
+    };
+    let reserved = matches!(
+        text.to_lowercase().as_str(),
+        "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n" | "null" | "~"
+    );
+    // Leading digits, dots and signs could read back as numbers or dates
+    !"-?:,[]{}#&*!|>'\"%@`.+ ".contains(first)
+        && !first.is_ascii_digit()
+        && !reserved
+        && !text.ends_with([' ', ':'])
+        && !text.contains(": ")
+        && !text.contains(" #")
+        && !text.chars().any(char::is_control)
+}
+
+// `|` block for multi-line text; the chomping indicator keeps trailing newlines exact
+fn literal_block(text: &str, indent: usize) -> Option<String> {
+    let body = text.trim_end_matches('\n');
+    let starts_cleanly = body.chars().next().is_some_and(|first| !first.is_whitespace());
+    if !body.contains('\n') || !starts_cleanly || body.chars().any(|c| c.is_control() && c != '\n' && c != '\t') {
+        return None;
+    }
+    
+    let trailing = text.len() - body.len();
+    let mut block = match trailing {
+        0 => "|-".to_string(),
+        1 => "|".to_string(),
+        _ => "|+".to_string(),
+    };
+    for line in body.split('\n') {
+        block.push('\n');
+        if !line.is_empty() {
+            block.push_str(&" ".repeat(indent));
+            block.push_str(line);
+        }
+    }
+    block.push_str(&"\n".repeat(trailing.saturating_sub(1)));
+    Some(block)
+}
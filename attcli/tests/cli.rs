@@ -0,0 +1,76 @@
+use std::process::{Command, Stdio};
+
+// attcli on the fixture bundle, shielded from the user's config file, cache and color settings
+fn attcli(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_attcli"));
+    command
+        .args(["--file", "tests/fixtures/bundle.json", "--no-cache", "--no-pager"])
+        .args(args)
+        .env("HOME", "tests/fixtures/no-home")
+        .env("TERM", "xterm-256color")
+        .env_remove("NO_COLOR")
+        .env_remove("XDG_DATA_HOME")
+        .stderr(Stdio::null());
+    command
+}
+
+// Output of the command with stdout on a pseudo-terminal, where colors and hyperlinks are on by default
+#[cfg(unix)]
+fn on_terminal(mut command: Command) -> String {
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::FromRawFd;
+    
+    let (mut controller, mut terminal) = (0, 0);
+    // SAFETY: openpty only writes the two new descriptors into the pointers it is given
+    let opened = unsafe { libc::openpty(&mut controller, &mut terminal, std::ptr::null_mut(), std::ptr::null(), std::ptr::null()) };
+    assert_eq!(opened, 0, "could not open a pseudo-terminal");
+    // SAFETY: both descriptors were just opened by openpty and are owned by nothing else
+    let (mut controller, terminal) = unsafe { (File::from_raw_fd(controller), File::from_raw_fd(terminal)) };
+    
+    let mut child = command.stdout(terminal).spawn().unwrap();
+    // The command holds our copy of the terminal side; reads end once the child's copy is closed too
+    drop(command);
+    let mut output = Vec::new();
+    let mut buffer = [0; 4096];
+    // Linux reports EIO rather than end of file once the terminal side is closed
+    while let Ok(read) = controller.read(&mut buffer) && read > 0 {
+        output.extend_from_slice(&buffer[..read]);
+    }
+    assert!(child.wait().unwrap().success());
+    String::from_utf8(output).unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn terminal_output_is_colored_by_default() {
+    assert!(on_terminal(attcli(&["tid", "T1055"])).contains('\x1b'));
+}
+
+#[cfg(unix)]
+#[test]
+fn no_color_output_has_no_escape_sequences() {
+    for args in [["tid", "T1055"], ["apt", "APT28"]] {
+        let output = on_terminal(attcli(&[&["--no-color"], &args[..]].concat()));
+        assert!(output.contains("Name:"));
+        assert!(!output.contains('\x1b'), "escape sequence in {:?}", output);
+    }
+    
+    let mut command = attcli(&["tid", "T1055"]);
+    command.env("NO_COLOR", "1");
+    assert!(!on_terminal(command).contains('\x1b'));
+}
+
+#[test]
+fn piped_output_has_no_escape_sequences() {
+    let output = attcli(&["tid", "T1055"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8(output.stdout).unwrap().contains('\x1b'));
+}
+
+#[test]
+fn unknown_tactic_fails_without_writing_to_stdout() {
+    let output = attcli(&["--json", "technique-list", "--tactic", "nosuch"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
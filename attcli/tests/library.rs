@@ -0,0 +1,816 @@
+use attcli::*;
+use std::path::{Path, PathBuf};
+
+fn fixture() -> AttackData {
+    load_attack_data(Path::new("tests/fixtures/bundle.json")).expect("fixture bundle should load")
+}
+
+// The fixture bundle as JSON, for tests that need a modified copy
+fn fixture_bundle() -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string("tests/fixtures/bundle.json").unwrap()).unwrap()
+}
+
+fn load_bundle(bundle: &serde_json::Value) -> AttackData {
+    parse_attack_data(&bundle.to_string()).expect("modified bundle should load")
+}
+
+// A scratch directory that is removed even when the test fails part way through
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let dir = std::env::temp_dir().join(format!("attcli-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn mitre_ids(objects: &[&AttackObject]) -> Vec<String> {
+    let mut ids: Vec<String> = objects.iter().filter_map(|obj| get_mitre_id(obj)).collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn loads_every_object() {
+    let data = fixture();
+    assert_eq!(data.objects.len(), 40);
+}
+
+#[test]
+fn missing_file_is_reported_as_not_found() {
+    let err = load_attack_data(Path::new("tests/fixtures/missing.json")).unwrap_err();
+    assert!(matches!(err, AttError::MatrixNotFound(_)));
+}
+
+#[test]
+fn missing_home_directory_is_an_error_not_a_panic() {
+    let err = data_dir_in(None, None).unwrap_err();
+    assert!(matches!(err, AttError::NoHomeDir));
+    assert!(err.to_string().contains("--file") && err.to_string().contains("ATTCLI_MATRIX"));
+}
+
+#[test]
+fn data_dir_follows_xdg_data_home() {
+    let home = || Some("/home/analyst".into());
+    assert_eq!(data_dir_in(home(), None).unwrap(), Path::new("/home/analyst/.local/share/attcli"));
+    assert_eq!(data_dir_in(home(), Some("/data".into())).unwrap(), Path::new("/data/attcli"));
+    assert_eq!(data_dir_in(home(), Some("relative".into())).unwrap(), Path::new("/home/analyst/.local/share/attcli"));
+    assert_eq!(data_dir_in(None, Some("/data".into())).unwrap(), Path::new("/data/attcli"));
+}
+
+#[test]
+fn malformed_file_is_a_parse_error() {
+    let err = load_attack_data(Path::new("tests/library.rs")).unwrap_err();
+    assert!(matches!(err, AttError::Parse(_)));
+}
+
+#[test]
+fn finds_technique_by_id_case_insensitively() {
+    let data = fixture();
+    let technique = find_technique_by_id("t1055", &data).expect("T1055 should exist");
+    assert_eq!(technique.name.as_deref(), Some("Process Injection"));
+    assert!(find_technique_by_id("T9999", &data).is_none());
+}
+
+#[test]
+fn related_techniques_are_deduplicated() {
+    let data = fixture();
+    let group = find_group("APT28", &data).expect("APT28 should exist");
+    let techniques = get_related_techniques(&group.id, &data);
+    assert_eq!(mitre_ids(&techniques), ["T1055", "T1566"]);
+}
+
+#[test]
+fn duplicate_uses_relationships_count_once() {
+    let data = fixture();
+    let group = find_group("APT28", &data).unwrap();
+    let phishing = find_technique_by_id("T1566", &data).unwrap();
+    let links = get_relationships(&group.id, &data)
+        .into_iter()
+        .filter(|relationship| relationship.target_ref.as_deref() == Some(phishing.id.as_str()))
+        .count();
+    assert_eq!(links, 2, "fixture should link APT28 to T1566 twice");
+    
+    let techniques = get_related_techniques(&group.id, &data);
+    assert_eq!(techniques.iter().filter(|technique| technique.id == phishing.id).count(), 1);
+    assert_eq!(get_related_groups(&phishing.id, &data).len(), 1);
+}
+
+#[test]
+fn related_groups_of_a_technique() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    let groups: Vec<&AttackObject> = get_related_groups(&technique.id, &data).into_iter().map(|(group, _)| group).collect();
+    assert_eq!(mitre_ids(&groups), ["G0007", "G0032"]);
+}
+
+#[test]
+fn related_groups_carry_the_procedure_example() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1566", &data).unwrap();
+    let groups = get_related_groups(&technique.id, &data);
+    assert_eq!(groups.len(), 1);
+    let (group, relationship) = groups[0];
+    assert_eq!(group.name.as_deref(), Some("APT28"));
+    assert_eq!(relationship.description.as_deref(), Some("APT28 has used phishing."));
+}
+
+#[test]
+fn groups_match_on_aliases() {
+    let data = fixture();
+    let groups = find_groups_by_name("fancy", &data);
+    assert_eq!(mitre_ids(&groups), ["G0007"]);
+}
+
+#[test]
+fn groups_are_found_by_mitre_id() {
+    let data = fixture();
+    assert_eq!(mitre_ids(&find_groups_by_name("G0016", &data)), ["G0016"]);
+    assert_eq!(mitre_ids(&find_groups_by_name("g0032", &data)), ["G0032"]);
+    assert_eq!(mitre_ids(&find_groups_by_exact_name("G0007", &data)), ["G0007"]);
+    assert!(find_groups_by_name("G1234", &data).is_empty());
+}
+
+#[test]
+fn exact_name_matches_need_the_whole_name_or_alias() {
+    let data = fixture();
+    assert!(find_groups_by_exact_name("apt", &data).is_empty());
+    assert_eq!(mitre_ids(&find_groups_by_exact_name("apt28", &data)), ["G0007"]);
+    assert_eq!(mitre_ids(&find_groups_by_exact_name("Fancy Bear", &data)), ["G0007"]);
+    assert!(find_techniques_by_exact_name("injection", &data).is_empty());
+    assert_eq!(mitre_ids(&find_techniques_by_exact_name("process injection", &data)), ["T1055"]);
+}
+
+#[test]
+fn platform_coverage_counts_techniques_per_platform() {
+    let data = fixture();
+    let group = find_group("APT28", &data).unwrap();
+    let techniques = get_related_techniques(&group.id, &data);
+    let coverage = platform_coverage(&techniques);
+    assert_eq!(coverage, [("Linux", 2), ("Windows", 2), ("macOS", 1)]);
+}
+
+#[test]
+fn subtechniques_link_both_ways() {
+    let data = fixture();
+    let parent = find_technique_by_id("T1055", &data).unwrap();
+    let children = get_subtechniques(&parent.id, &data);
+    assert_eq!(mitre_ids(&children), ["T1055.012"]);
+
+    let child = find_technique_by_id("T1055.012", &data).unwrap();
+    let found_parent = get_parent_technique(&child.id, &data).unwrap();
+    assert_eq!(found_parent.id, parent.id);
+}
+
+#[test]
+fn id_prefixes_list_a_family_of_techniques() {
+    let data = fixture();
+    assert_eq!(mitre_ids(&find_techniques_by_id_prefix("t1055.", &data)), ["T1055.012"]);
+    assert_eq!(mitre_ids(&find_techniques_by_id_prefix("T1", &data)), ["T1055", "T1055.012", "T1566", "T1566.001", "T1999"]);
+    assert!(find_techniques_by_id_prefix("T9", &data).is_empty());
+    assert!(find_techniques_by_id_prefix(" ", &data).is_empty());
+}
+
+#[test]
+fn dotted_subtechnique_ids_match_case_insensitively() {
+    let data = fixture();
+    let child = find_technique_by_id("t1055.012", &data).unwrap();
+    assert_eq!(get_mitre_id(child).as_deref(), Some("T1055.012"));
+    assert!(find_technique_by_id("T1055012", &data).is_none());
+}
+
+#[test]
+fn revoked_objects_resolve_to_replacement() {
+    let data = fixture();
+    let revoked = find_technique_by_id("T1888", &data).unwrap();
+    let replacement = resolve_revoked(revoked, &data);
+    assert_eq!(get_mitre_id(replacement).as_deref(), Some("T1055"));
+}
+
+#[test]
+fn sunset_techniques_name_their_replacement_when_revoked() {
+    let data = fixture();
+    let sunset: Vec<(String, Option<String>)> = list_sunset_techniques(None, &[], PlatformMatch::Any, true, &data)
+        .into_iter()
+        .map(|obj| (get_mitre_id(obj).unwrap(), replacement_of(obj, &data).and_then(get_mitre_id)))
+        .collect();
+    assert_eq!(sunset, [("T1888".to_string(), Some("T1055".to_string())), ("T1999".to_string(), None)]);
+}
+
+#[test]
+fn search_terms_need_every_word_unless_any_is_set() {
+    let data = fixture();
+    let ids = |found: &[SearchMatch]| {
+        let objects: Vec<&AttackObject> = found.iter().map(|entry| entry.object).collect();
+        mitre_ids(&objects)
+    };
+    
+    let both = search_terms("bear FANCY", false, None, &data);
+    assert_eq!(ids(&both), ["G0007"]);
+    assert_eq!(both[0].terms, [("bear".to_string(), MatchField::Alias), ("fancy".to_string(), MatchField::Alias)]);
+    
+    assert_eq!(ids(&search_terms("fancy cozy", true, Some(ObjectKind::Group), &data)), ["G0007", "G0016"]);
+    assert_eq!(ids(&search_terms("bear", false, None, &data)), ["G0007", "G0016"]);
+    assert!(search_terms("bear", false, Some(ObjectKind::Technique), &data).is_empty());
+    assert!(search_terms("fancy cozy", false, None, &data).is_empty());
+    assert!(search_terms("   ", true, None, &data).is_empty());
+}
+
+#[test]
+fn title_cases_phase_names() {
+    assert_eq!(title_case_phase_name("privilege-escalation"), "Privilege Escalation");
+    assert_eq!(title_case_phase_name("command-and-control"), "Command And Control");
+}
+
+#[test]
+fn title_casing_handles_empty_multibyte_and_doubled_hyphen_segments() {
+    assert_eq!(title_case_phase_name(""), "");
+    assert_eq!(title_case_phase_name("-"), "");
+    assert_eq!(title_case_phase_name("--command--and-control-"), "Command And Control");
+    assert_eq!(title_case_phase_name("évasion-ñame"), "Évasion Ñame");
+    assert_eq!(title_case_phase_name("ßtrasse"), "SStrasse");
+}
+
+#[test]
+fn platform_filter_is_case_insensitive() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    assert!(matches_platforms(technique, &[], PlatformMatch::Any));
+    assert!(matches_platforms(technique, &["linux".to_string()], PlatformMatch::Any));
+    assert!(!matches_platforms(technique, &["macOS".to_string()], PlatformMatch::Any));
+}
+
+#[test]
+fn technique_count_separates_parents_from_subtechniques() {
+    let data = fixture();
+    let parent = find_technique_by_id("T1055", &data).unwrap();
+    let sub = find_technique_by_id("T1055.012", &data).unwrap();
+    assert!(TechniqueCount::All.includes(parent) && TechniqueCount::All.includes(sub));
+    assert!(TechniqueCount::Parents.includes(parent) && !TechniqueCount::Parents.includes(sub));
+    assert!(!TechniqueCount::Subs.includes(parent) && TechniqueCount::Subs.includes(sub));
+}
+
+#[test]
+fn platform_match_all_requires_every_platform() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    let platforms = ["Windows".to_string(), "macOS".to_string()];
+    assert!(matches_platforms(technique, &platforms, PlatformMatch::Any));
+    assert!(!matches_platforms(technique, &platforms, PlatformMatch::All));
+    assert!(matches_platforms(technique, &platforms[..1], PlatformMatch::All));
+    
+    let ids = |techniques: Vec<&AttackObject>| techniques.iter().filter_map(|obj| get_mitre_id(obj)).collect::<Vec<_>>();
+    assert_eq!(ids(list_techniques(None, &platforms, PlatformMatch::All, false, &data)), ["T1566"]);
+}
+
+#[test]
+fn tactics_follow_kill_chain_order() {
+    let data = fixture();
+    let shortnames: Vec<&str> = get_tactics_in_kill_chain_order(&data)
+        .iter()
+        .filter_map(|tactic| tactic.shortname.as_deref())
+        .collect();
+    assert_eq!(shortnames, ["initial-access", "privilege-escalation", "defense-evasion"]);
+}
+
+#[test]
+fn tactic_sections_follow_the_matrix_order() {
+    let data = fixture();
+    assert_eq!(tactic_sequence(&data), ["initial-access", "privilege-escalation", "defense-evasion"]);
+    
+    let group = find_group("APT28", &data).expect("APT28 should exist");
+    let techniques = get_related_techniques(&group.id, &data);
+    let tactics = |order| -> Vec<String> {
+        group_techniques_in_order(&techniques, order, &data).into_iter().map(|(tactic, _)| tactic).collect()
+    };
+    assert_eq!(tactics(TacticOrder::Killchain), ["Initial Access", "Privilege Escalation", "Defense Evasion"]);
+    assert_eq!(tactics(TacticOrder::Alpha), ["Defense Evasion", "Initial Access", "Privilege Escalation"]);
+}
+
+#[test]
+fn suggestions_include_aliases() {
+    let data = fixture();
+    let suggestions = suggest_similar("fancy baer", ObjectKind::Group, &data, 5);
+    let (group, matched_name) = suggestions.first().expect("should suggest something");
+    assert_eq!(get_mitre_id(group).as_deref(), Some("G0007"));
+    assert_eq!(*matched_name, "Fancy Bear");
+}
+
+#[test]
+fn domain_check_uses_x_mitre_domains() {
+    let data = fixture();
+    assert!(data.matches_domain(Domain::Enterprise));
+    assert!(!data.matches_domain(Domain::Mobile));
+}
+
+#[test]
+fn deprecated_objects_are_flagged() {
+    let data = fixture();
+    let retired = find_group("Retired Group", &data).expect("deprecated groups are still loaded");
+    assert!(retired.is_deprecated());
+    assert!(!find_group("APT28", &data).unwrap().is_deprecated());
+    assert!(find_technique_by_id("T1999", &data).unwrap().is_deprecated());
+    assert!(!find_technique_by_id("T1055", &data).unwrap().is_deprecated());
+}
+
+#[test]
+fn relationships_cover_both_directions() {
+    let data = fixture();
+    let technique = find_object("T1055", &data).expect("MITRE IDs resolve");
+    assert_eq!(find_object(&technique.id, &data).map(|obj| &obj.id), Some(&technique.id));
+    
+    let mut types: Vec<&str> = get_relationships(&technique.id, &data)
+        .iter()
+        .filter_map(|relationship| relationship.relationship_type.as_deref())
+        .collect();
+    types.sort();
+    assert_eq!(types, ["detects", "mitigates", "related-to", "revoked-by", "subtechnique-of", "uses", "uses"]);
+}
+
+#[test]
+fn attack_urls_prefer_the_reference_and_fall_back_to_the_id() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    assert_eq!(attack_url(technique).as_deref(), Some("https://attack.mitre.org/x/T1055"));
+    assert_eq!(
+        canonical_attack_url("attack-pattern", "T1055.012").as_deref(),
+        Some("https://attack.mitre.org/techniques/T1055/012/")
+    );
+    assert_eq!(canonical_attack_url("intrusion-set", "G0007").as_deref(), Some("https://attack.mitre.org/groups/G0007/"));
+    assert!(canonical_attack_url("relationship", "R1").is_none());
+}
+
+#[test]
+fn mitre_ids_sort_numerically_with_subtechniques_after_parents() {
+    let mut ids = ["T1059", "T1055.001", "T999", "T1055", "T1055.012"];
+    ids.sort_by(|a, b| compare_mitre_ids(a, b));
+    assert_eq!(ids, ["T999", "T1055", "T1055.001", "T1055.012", "T1059"]);
+    
+    let data = fixture();
+    let mut groups = find_groups_by_name("apt", &data);
+    sort_objects(&mut groups, SortOrder::Id);
+    let ids: Vec<String> = groups.iter().filter_map(|group| get_mitre_id(group)).collect();
+    assert_eq!(ids, ["G0007", "G0016"]);
+}
+
+#[test]
+fn tactic_aliases_resolve_to_the_official_shortname() {
+    for alias in ["c2", "CnC", "c&c", "command-and-control", "Command and Control"] {
+        assert_eq!(normalize_tactic_name(alias), "command_and_control", "{}", alias);
+    }
+    assert_eq!(normalize_tactic_name("Exfil"), normalize_tactic_name("exfiltration"));
+    
+    let data = fixture();
+    let tactics = find_tactics_by_name("privesc", &data);
+    assert_eq!(tactics.iter().map(|tactic| tactic.shortname.as_deref()).collect::<Vec<_>>(), [Some("privilege-escalation")]);
+    assert_eq!(list_techniques(Some("priv-esc"), &[], PlatformMatch::Any, false, &data).len(), 1);
+}
+
+#[test]
+fn technique_list_filters_by_tactic_and_leaves_out_subtechniques() {
+    let data = fixture();
+    let ids = |techniques: Vec<&AttackObject>| techniques.iter().filter_map(|obj| get_mitre_id(obj)).collect::<Vec<_>>();
+    assert_eq!(ids(list_techniques(None, &[], PlatformMatch::Any, false, &data)), ["T1055", "T1566", "T1999"]);
+    assert_eq!(ids(list_techniques(Some("Privilege Escalation"), &[], PlatformMatch::Any, true, &data)), ["T1055", "T1055.012"]);
+    assert_eq!(ids(list_techniques(Some("privilege-escalation"), &[], PlatformMatch::Any, false, &data)), ["T1055"]);
+}
+
+#[test]
+fn data_sources_match_whole_entries_or_the_source_name() {
+    let data = fixture();
+    let by_component = find_techniques_by_data_source("process: process creation", &data);
+    let by_source = find_techniques_by_data_source("Process", &data);
+    assert_eq!(mitre_ids(&by_component), ["T1055", "T1055.012", "T1566", "T1566.001"]);
+    assert_eq!(mitre_ids(&by_source), mitre_ids(&by_component));
+    assert!(find_techniques_by_data_source("Creation", &data).is_empty());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_index_matches_the_sequential_one() {
+    let data = fixture();
+    assert_eq!(Index::build_parallel(&data.objects), Index::build_sequential(&data.objects));
+}
+
+#[test]
+fn cached_and_fresh_loads_agree() {
+    let dir = TempDir::new("cache-test");
+    let matrix = dir.0.join("matrix.json");
+    std::fs::copy("tests/fixtures/bundle.json", &matrix).unwrap();
+    
+    let cache = cache_path_in(&dir.0.join("cache"), &matrix);
+    assert!(read_cache(&cache, &matrix).is_none());
+    let fresh = load_attack_data(&matrix).unwrap();
+    write_cache(&cache, &fresh).unwrap();
+    let cached = read_cache(&cache, &matrix).unwrap();
+    assert!(!std::fs::read_to_string(&cache).unwrap().contains("null"));
+    
+    assert_eq!(cached.objects.len(), fresh.objects.len());
+    for data in [&fresh, &cached] {
+        let group = find_group("Fancy Bear", data).unwrap();
+        assert_eq!(mitre_ids(&get_related_techniques(&group.id, data)), ["T1055", "T1566"]);
+        assert_eq!(find_technique_by_id("T1055.012", data).unwrap().name.as_deref(), Some("Process Hollowing"));
+    }
+}
+
+#[test]
+fn technique_name_matches_list_a_repeated_object_once() {
+    let mut bundle = fixture_bundle();
+    let objects = bundle["objects"].as_array_mut().unwrap();
+    let phishing = objects.iter().find(|obj| obj["name"] == "Phishing").unwrap().clone();
+    objects.push(phishing);
+    let data = load_bundle(&bundle);
+    
+    let found = find_techniques_by_name("phishing", &data);
+    let phishing_count = |objects: &[&AttackObject]| objects.iter().filter(|obj| obj.name.as_deref() == Some("Phishing")).count();
+    assert_eq!(phishing_count(&found), 2);
+    let deduped = dedupe_by_id(found);
+    assert_eq!(phishing_count(&deduped), 1);
+    assert_eq!(mitre_ids(&deduped), ["T1566", "T1566.001"]);
+}
+
+#[test]
+fn release_diff_reports_added_removed_and_changed_objects() {
+    let mut bundle = fixture_bundle();
+    let objects = bundle["objects"].as_array_mut().unwrap();
+    objects.retain(|obj| obj["name"] != "Lazarus Group");
+    let phishing = objects.iter_mut().find(|obj| obj["name"] == "Phishing").unwrap();
+    phishing["x_mitre_version"] = "2.0".into();
+    phishing["x_mitre_deprecated"] = true.into();
+    let hollowing = objects.iter_mut().find(|obj| obj["name"] == "Process Hollowing").unwrap();
+    hollowing["revoked"] = true.into();
+    objects.push(serde_json::json!({
+        "type": "attack-pattern",
+        "id": "attack-pattern--new",
+        "name": "New Technique",
+        "external_references": [{ "source_name": "mitre-attack", "external_id": "T1700" }],
+    }));
+    
+    let new = load_bundle(&bundle);
+    
+    let old = fixture();
+    let diff = diff_releases(&old, &new);
+    assert_eq!(mitre_ids(&diff.added), ["T1700"]);
+    assert_eq!(mitre_ids(&diff.removed), ["G0032"]);
+    assert_eq!(mitre_ids(&diff.deprecated), ["T1566"]);
+    assert_eq!(mitre_ids(&diff.revoked), ["T1055.012"]);
+    let bumps: Vec<(Option<&str>, Option<&str>)> = diff.version_bumps.iter()
+        .map(|(before, after)| (before.version.as_deref(), after.version.as_deref()))
+        .collect();
+    assert_eq!(bumps, [(Some("1.2"), Some("2.0"))]);
+    
+    let unchanged = diff_releases(&old, &old);
+    assert!(unchanged.added.is_empty() && unchanged.removed.is_empty() && unchanged.version_bumps.is_empty());
+}
+
+#[test]
+fn validation_reports_broken_references_and_duplicates() {
+    assert!(validate_bundle(&fixture()).is_empty());
+    
+    let mut bundle = fixture_bundle();
+    let objects = bundle["objects"].as_array_mut().unwrap();
+    let phishing = objects.iter_mut().find(|obj| obj["name"] == "Phishing").unwrap();
+    phishing.as_object_mut().unwrap().remove("kill_chain_phases");
+    let mut copy = phishing.clone();
+    copy["id"] = "attack-pattern--copy".into();
+    objects.push(copy);
+    objects.push(serde_json::json!({
+        "type": "relationship",
+        "id": "relationship--dangling",
+        "relationship_type": "uses",
+        "source_ref": "intrusion-set--missing",
+        "target_ref": "attack-pattern--copy",
+    }));
+    
+    let data = load_bundle(&bundle);
+    
+    let issues = validate_bundle(&data);
+    let problems: Vec<(&str, &Problem)> = issues.iter()
+        .map(|issue| (issue.object.id.as_str(), &issue.problem))
+        .collect();
+    assert_eq!(problems, [
+        ("relationship--dangling", &Problem::MissingSource("intrusion-set--missing".to_string())),
+        ("attack-pattern--1", &Problem::NoKillChainPhases),
+        ("attack-pattern--copy", &Problem::NoKillChainPhases),
+        ("attack-pattern--copy", &Problem::DuplicateMitreId("T1566".to_string())),
+    ]);
+}
+
+#[test]
+fn group_comparison_splits_shared_and_unique_techniques() {
+    let data = fixture();
+    let apt28 = find_group("APT28", &data).unwrap();
+    let lazarus = find_group("Lazarus Group", &data).unwrap();
+    let overlap = compare_group_techniques(&apt28.id, &lazarus.id, &data);
+    
+    assert_eq!(mitre_ids(&overlap.shared), ["T1055"]);
+    assert_eq!(mitre_ids(&overlap.only_first), ["T1566"]);
+    assert_eq!(mitre_ids(&overlap.only_second), ["T1566.001"]);
+    assert!((overlap.jaccard() - 1.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn groups_rank_by_observed_techniques() {
+    let data = fixture();
+    let observed: Vec<&str> = ["T1055", "T1566.001"]
+        .iter()
+        .map(|id| find_technique_by_id(id, &data).unwrap().id.as_str())
+        .collect();
+    let ranking = rank_groups_by_techniques(&observed, &data);
+    
+    let summary: Vec<(Option<String>, usize)> = ranking.iter().map(|entry| (get_mitre_id(entry.group), entry.matched)).collect();
+    assert_eq!(summary, [(Some("G0032".to_string()), 2), (Some("G0007".to_string()), 1)]);
+}
+
+#[test]
+fn techniques_rank_by_the_groups_using_them() {
+    let data = fixture();
+    let summary: Vec<(Option<String>, usize)> = rank_techniques_by_groups(None, &data)
+        .iter()
+        .map(|entry| (get_mitre_id(entry.technique), entry.groups))
+        .collect();
+    assert_eq!(summary, [
+        (Some("T1055".to_string()), 2),
+        (Some("T1566".to_string()), 1),
+        (Some("T1566.001".to_string()), 1),
+    ]);
+    
+    let initial_access = rank_techniques_by_groups(Some("Initial Access"), &data);
+    let ids: Vec<Option<String>> = initial_access.iter().map(|entry| get_mitre_id(entry.technique)).collect();
+    assert_eq!(ids, [Some("T1566".to_string()), Some("T1566.001".to_string())]);
+}
+
+#[test]
+fn groups_rank_by_their_technique_count() {
+    let data = fixture();
+    let summary: Vec<(Option<String>, usize)> = rank_groups_by_technique_count(None, &data)
+        .iter()
+        .map(|entry| (get_mitre_id(entry.group), entry.techniques))
+        .collect();
+    assert_eq!(summary, [(Some("G0007".to_string()), 2), (Some("G0032".to_string()), 2)]);
+    
+    let evasion = rank_groups_by_technique_count(Some("defense-evasion"), &data);
+    assert!(evasion.iter().all(|entry| entry.techniques == 1));
+    assert!(rank_groups_by_technique_count(Some("impact"), &data).is_empty());
+}
+
+#[test]
+fn renamed_techniques_resolve_by_their_previous_name() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1566.001", &data).unwrap();
+    assert_eq!(previous_names(technique), ["Spearphishing via Attachment"]);
+    
+    assert_eq!(mitre_ids(&find_techniques_by_name("via attachment", &data)), ["T1566.001"]);
+    assert_eq!(mitre_ids(&find_techniques_by_exact_name("spearphishing via attachment", &data)), ["T1566.001"]);
+    assert!(find_techniques_by_exact_name("via attachment", &data).is_empty());
+}
+
+#[test]
+fn object_types_are_named_in_attack_terms() {
+    let data = fixture();
+    let kinds: Vec<&str> = ["T1055", "t1055.012", "G0007", "S0161", "M1026", "TA0001", "C0001"]
+        .iter()
+        .map(|id| object_type_name(find_object(id, &data).unwrap()))
+        .collect();
+    assert_eq!(kinds, ["technique", "sub-technique", "group", "malware", "mitigation", "tactic", "campaign"]);
+    assert_eq!(object_type_name(find_object("location--1", &data).unwrap()), "location");
+}
+
+#[test]
+fn subtechniques_nest_under_their_parent() {
+    let data = fixture();
+    let techniques = list_techniques(Some("privilege-escalation"), &[], PlatformMatch::Any, true, &data);
+    let trees = nest_subtechniques(&techniques, &data);
+    
+    assert_eq!(trees.len(), 1);
+    assert_eq!(get_mitre_id(trees[0].technique).as_deref(), Some("T1055"));
+    assert_eq!(mitre_ids(&trees[0].subtechniques), ["T1055.012"]);
+    
+    let orphan = [find_technique_by_id("T1055.012", &data).unwrap()];
+    let trees = nest_subtechniques(&orphan, &data);
+    assert_eq!((trees.len(), trees[0].subtechniques.len()), (1, 0));
+}
+
+#[test]
+fn groups_list_their_software_and_campaigns() {
+    let data = fixture();
+    let apt28 = find_group("APT28", &data).unwrap();
+    assert_eq!(mitre_ids(&get_group_software(&apt28.id, &data)), ["S0161"]);
+    assert_eq!(mitre_ids(&get_group_campaigns(&apt28.id, &data)), ["C0001"]);
+    
+    let lazarus = find_group("G0032", &data).unwrap();
+    assert!(get_group_software(&lazarus.id, &data).is_empty());
+    assert!(get_group_campaigns(&lazarus.id, &data).is_empty());
+}
+
+#[test]
+fn mitigation_gaps_stay_within_the_addressed_tactics() {
+    let data = fixture();
+    let gaps = mitigation_gaps(&find_mitigation_by_id("M1026", &data).unwrap().id, &data);
+    
+    let tactics: Vec<&str> = gaps.iter().map(|entry| entry.tactic).collect();
+    assert_eq!(tactics, ["privilege-escalation", "defense-evasion"]);
+    assert!(gaps.iter().all(|entry| entry.covered() == 1 && mitre_ids(&entry.missing) == ["T1055.012"]));
+    
+    let phishing = mitigation_gaps(&find_mitigation_by_id("M1017", &data).unwrap().id, &data);
+    assert_eq!((phishing.len(), phishing[0].missing.len()), (1, 0));
+}
+
+#[test]
+fn tactic_coverage_lists_missing_techniques_in_kill_chain_order() {
+    let data = fixture();
+    let covered: std::collections::HashSet<&str> = [find_technique_by_id("T1566", &data).unwrap().id.as_str()].into();
+    let coverage = tactic_coverage(&covered, &data);
+    
+    let tactics: Vec<&str> = coverage.iter().map(|entry| entry.tactic).collect();
+    assert_eq!(tactics, ["initial-access", "privilege-escalation", "defense-evasion"]);
+    assert_eq!((coverage[0].covered(), coverage[0].total), (1, 2));
+    assert_eq!(mitre_ids(&coverage[0].missing), ["T1566.001"]);
+}
+
+#[test]
+fn config_parses_flat_settings_and_rejects_unknown_keys() {
+    let config = Config::parse("# defaults\ndomain = \"ICS\"\ncolor = false # no escapes\nmatrix_path = \"/data/#1/ics.json\"\n").unwrap();
+    assert_eq!(config.domain, Some(Domain::Ics));
+    assert_eq!(config.color, Some(false));
+    assert_eq!(config.matrix_path.as_deref(), Some(Path::new("/data/#1/ics.json")));
+    assert_eq!(config.format, None);
+    assert_eq!(config.references, None);
+    assert_eq!(Config::parse("references = false").unwrap().references, Some(false));
+    
+    assert!(Config::parse("colour = true").unwrap_err().contains("unknown setting"));
+    assert!(Config::parse("format = table").is_err());
+    assert_eq!(Config::load(Path::new("tests/fixtures/missing.toml")).unwrap(), Config::default());
+}
+
+#[test]
+fn last_modified_is_the_newest_object_timestamp() {
+    let data = fixture();
+    assert_eq!(last_modified(&data), Some("2024-04-23T00:00:00.000Z"));
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    assert_eq!(technique.created.as_deref(), Some("2019-01-01T00:00:00.000Z"));
+    assert_eq!(technique.contributors.as_deref(), Some(&["Jane Doe".to_string()][..]));
+}
+
+#[test]
+fn modified_since_groups_by_kind_newest_first() {
+    let data = fixture();
+    let ids: Vec<String> = modified_since("2023-09-01", &data).iter().filter_map(|obj| get_mitre_id(obj)).collect();
+    assert_eq!(ids[..3], ["T1566", "T1055", "T1055.012"]);
+    assert!(ids[3..].iter().all(|id| id.starts_with('G')));
+    assert!(modified_since("2030-01-01", &data).is_empty());
+}
+
+#[test]
+fn attack_version_comes_from_the_bundle() {
+    let data = fixture();
+    assert_eq!(attack_version(&data), Some("15.1"));
+    assert_eq!(
+        Domain::Mobile.download_url(),
+        "https://raw.githubusercontent.com/mitre-attack/attack-stix-data/master/mobile-attack/mobile-attack.json"
+    );
+}
+
+#[test]
+fn yaml_output_quotes_ambiguous_strings_and_keeps_field_names() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    let yaml = to_yaml(&serde_json::to_value(technique).unwrap());
+    assert!(yaml.contains("x_mitre_version: \"1.2\"\n"));
+    assert!(yaml.contains("x_mitre_platforms:\n  - Windows\n  - Linux\n"));
+    assert!(!yaml.contains("null"));
+    
+    let value = serde_json::json!([{"name": "yes", "notes": "first\nsecond"}, "a: b"]);
+    assert_eq!(to_yaml(&value), "- name: \"yes\"\n  notes: |-\n    first\n    second\n- \"a: b\"\n");
+}
+
+#[test]
+fn first_sentence_drops_citations_and_links_and_caps_length() {
+    let description = "[APT28](https://attack.mitre.org/groups/G0007) is a  threat group.(Citation: Some Report) It does things.";
+    assert_eq!(plain_description(description), "APT28 is a  threat group. It does things.");
+    assert_eq!(first_sentence(description, 100), "APT28 is a threat group.");
+    assert_eq!(first_sentence(description, 12), "APT28 is a…");
+    assert_eq!(first_sentence("No full stop", 100), "No full stop");
+}
+
+#[test]
+fn first_sentence_skips_abbreviations_and_initials() {
+    let text = "Adversaries may abuse tools, e.g. PowerShell, from Microsoft Corp. and J. Smith's U.S. lab. Then more.";
+    assert_eq!(first_sentence(text, 200), "Adversaries may abuse tools, e.g. PowerShell, from Microsoft Corp. and J. Smith's U.S. lab.");
+}
+
+#[test]
+fn detection_search_returns_the_match_in_context() {
+    let data = fixture();
+    let found = search_detections("PROCESS HOLLOWING", 8, &data);
+    assert_eq!(found.len(), 1);
+    let (technique, snippet) = &found[0];
+    assert_eq!(get_mitre_id(technique).as_deref(), Some("T1055.012"));
+    assert_eq!((snippet.before, snippet.matched, snippet.after), ("tor for ", "process hollowing", "."));
+    assert!(snippet.clipped_start && !snippet.clipped_end);
+    
+    let snippet = find_snippet("Straße und STRASSE", "strasse", 3).unwrap();
+    assert_eq!(snippet.matched, "STRASSE");
+}
+
+#[test]
+fn group_footprint_adds_software_techniques_once() {
+    let data = fixture();
+    let group = find_group("APT28", &data).unwrap();
+    let footprint = get_all_group_techniques(&group.id, &data);
+    
+    let labelled: Vec<(String, Vec<&str>)> = footprint.iter()
+        .map(|entry| (
+            get_mitre_id(entry.technique).unwrap(),
+            entry.via.iter().filter_map(|tool| tool.name.as_deref()).collect(),
+        ))
+        .collect();
+    assert_eq!(labelled.len(), 3);
+    assert!(labelled.contains(&("T1055.012".to_string(), vec!["X-Agent"])));
+    assert!(labelled.contains(&("T1055".to_string(), vec![])));
+}
+
+#[test]
+fn data_components_detecting_a_technique_come_with_their_source() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1055", &data).unwrap();
+    let components = get_detecting_components(&technique.id, &data);
+    assert_eq!(components.len(), 1);
+    let (component, source) = components[0];
+    assert_eq!(component.name.as_deref(), Some("Process Creation"));
+    assert_eq!(source.and_then(get_mitre_id).as_deref(), Some("DS0009"));
+    
+    let phishing = find_technique_by_id("T1566", &data).unwrap();
+    assert!(get_detecting_components(&phishing.id, &data).is_empty());
+}
+
+#[test]
+fn group_regions_come_from_structured_hints_only() {
+    let data = fixture();
+    let apt28 = find_group("APT28", &data).unwrap();
+    assert_eq!(group_regions(apt28, &data), ["Russia"]);
+    let lazarus = find_group("Lazarus Group", &data).unwrap();
+    assert_eq!(group_regions(lazarus, &data), ["North Korea"]);
+    let retired = find_group("G9999", &data).unwrap();
+    assert!(group_regions(retired, &data).is_empty());
+}
+
+#[test]
+fn related_to_links_work_in_both_directions() {
+    let data = fixture();
+    let injection = find_technique_by_id("T1055", &data).unwrap();
+    let phishing = find_technique_by_id("T1566", &data).unwrap();
+    assert_eq!(mitre_ids(&get_linked_techniques(&injection.id, &data)), ["T1566"]);
+    assert_eq!(mitre_ids(&get_linked_techniques(&phishing.id, &data)), ["T1055"]);
+    
+    let hollowing = find_technique_by_id("T1055.012", &data).unwrap();
+    assert!(get_linked_techniques(&hollowing.id, &data).is_empty());
+}
+
+#[test]
+fn technique_mitigations_carry_their_guidance() {
+    let data = fixture();
+    let technique = find_technique_by_id("T1566", &data).unwrap();
+    let mitigations = get_mitigations_for_technique(&technique.id, &data);
+    
+    assert_eq!(mitigations.len(), 1);
+    let (mitigation, relationship) = mitigations[0];
+    assert_eq!(get_mitre_id(mitigation).as_deref(), Some("M1017"));
+    assert_eq!(relationship.description.as_deref(), Some("Train users to spot phishing."));
+}
+
+#[test]
+fn mitigations_rank_by_techniques_covered() {
+    let data = fixture();
+    let techniques: Vec<&AttackObject> = ["T1055", "T1566", "T1566.001", "T1566"]
+        .iter()
+        .map(|id| find_technique_by_id(id, &data).unwrap())
+        .collect();
+    let ranking = rank_mitigations_for_techniques(&techniques, &data);
+    
+    let summary: Vec<(String, usize)> = ranking.iter()
+        .map(|entry| (get_mitre_id(entry.mitigation).unwrap(), entry.covered.len()))
+        .collect();
+    assert_eq!(summary, [("M1017".to_string(), 2), ("M1026".to_string(), 1)]);
+}
+
+#[test]
+fn data_sources_are_ranked_by_techniques_covered() {
+    let data = fixture();
+    let group = find_groups_by_exact_name("APT28", &data)[0];
+    let mut techniques = get_related_techniques(&group.id, &data);
+    techniques.extend(techniques.clone());
+    let ranking = rank_data_sources(&techniques);
+    
+    let summary: Vec<(&str, Vec<String>)> = ranking.iter()
+        .map(|entry| (entry.data_source, entry.covered.iter().filter_map(|technique| get_mitre_id(technique)).collect()))
+        .collect();
+    assert_eq!(summary, [("Process: Process Creation", vec!["T1566".to_string(), "T1055".to_string()])]);
+}
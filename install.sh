@@ -1,11 +1,13 @@
 #!/usr/bin/env bash
 set -e
 
-echo "[-] Creating directory ~/.mitre/"
-mkdir -p  ~/.mitre/
+DATA_DIR="${XDG_DATA_HOME:-$HOME/.local/share}/attcli"
+
+echo "[-] Creating directory $DATA_DIR/"
+mkdir -p "$DATA_DIR"
 
 echo "[-] Getting MITRE ATT&CK Matrix JSON"
-curl -L "https://raw.githubusercontent.com/mitre-attack/attack-stix-data/master/enterprise-attack/enterprise-attack.json" > ~/.mitre/matrix.json
+curl -L "https://raw.githubusercontent.com/mitre-attack/attack-stix-data/master/enterprise-attack/enterprise-attack.json" > "$DATA_DIR/enterprise-attack.json"
 
 echo "[-] Compiling Rust binary"
 cd ./attcli